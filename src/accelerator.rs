@@ -0,0 +1,41 @@
+use crate::iokit::{get_i64, get_value, service_properties};
+use core_foundation_sys::{
+    base::{CFRelease, CFTypeRef},
+    dictionary::CFDictionaryRef,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AcceleratorStats {
+    /// Unified memory currently mapped by the GPU, in bytes.
+    pub in_use_memory_bytes: Option<u64>,
+    /// GPU busy percentage as the driver sees it.
+    pub device_utilization_pct: Option<u64>,
+}
+
+impl AcceleratorStats {
+    pub fn in_use_memory_gb(&self) -> Option<f32> {
+        self.in_use_memory_bytes
+            .map(|bytes| bytes as f32 / 1024.0 / 1024.0 / 1024.0)
+    }
+}
+
+/// Read the AGX accelerator's `PerformanceStatistics`. Returns `None` when
+/// the GPU driver does not publish them.
+pub fn read_accelerator() -> Option<AcceleratorStats> {
+    let properties = service_properties(c"IOAccelerator")?;
+    let stats = get_value(properties as CFDictionaryRef, "PerformanceStatistics")
+        .map(|statistics| parse_statistics(statistics as CFDictionaryRef));
+    unsafe {
+        CFRelease(properties as CFTypeRef);
+    }
+    stats
+}
+
+fn parse_statistics(dict: CFDictionaryRef) -> AcceleratorStats {
+    AcceleratorStats {
+        in_use_memory_bytes: get_i64(dict, "In use system memory").map(|bytes| bytes.max(0) as u64),
+        device_utilization_pct: get_i64(dict, "Device Utilization %")
+            .map(|pct| pct.clamp(0, 100) as u64),
+    }
+}
@@ -0,0 +1,66 @@
+use crate::iokit::{get_i64, get_value, service_properties};
+use core_foundation_sys::{
+    base::{CFRelease, CFTypeRef},
+    dictionary::CFDictionaryRef,
+    number::{CFBooleanGetValue, CFBooleanRef},
+};
+use serde::{Deserialize, Serialize};
+
+/// `AvgTimeToEmpty` reports this while macOS is still estimating.
+const TIME_UNKNOWN: i64 = 65535;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BatteryStats {
+    pub charge_percent: u64,
+    pub charging: bool,
+    /// Running on AC / USB-C power.
+    pub external_power: bool,
+    /// Minutes until empty, when discharging and macOS has an estimate.
+    pub time_to_empty_min: Option<u64>,
+    /// Instantaneous system draw in watts, from the adapter when plugged in
+    /// or from the battery otherwise.
+    pub system_power_w: f32,
+}
+
+/// Read the `AppleSmartBattery` controller. Returns `None` on machines
+/// without a battery.
+pub fn read_battery() -> Option<BatteryStats> {
+    let properties = service_properties(c"AppleSmartBattery")?;
+    let stats = parse_battery(properties as CFDictionaryRef);
+    unsafe {
+        CFRelease(properties as CFTypeRef);
+    }
+    stats
+}
+
+fn parse_battery(dict: CFDictionaryRef) -> Option<BatteryStats> {
+    let current = get_i64(dict, "CurrentCapacity")?;
+    let max = get_i64(dict, "MaxCapacity").filter(|max| *max > 0)?;
+    let charging = get_bool(dict, "IsCharging").unwrap_or(false);
+    let external_power = get_bool(dict, "ExternalConnected").unwrap_or(false);
+    let time_to_empty_min = get_i64(dict, "AvgTimeToEmpty")
+        .filter(|min| !external_power && *min > 0 && *min < TIME_UNKNOWN)
+        .map(|min| min as u64);
+
+    let adapter_mw = get_value(dict, "PowerTelemetryData")
+        .and_then(|telemetry| get_i64(telemetry as CFDictionaryRef, "SystemPowerIn"))
+        .filter(|mw| external_power && *mw > 0);
+    let battery_mw = match (get_i64(dict, "Voltage"), get_i64(dict, "InstantAmperage")) {
+        (Some(mv), Some(ma)) => (mv * ma).abs() / 1000,
+        _ => 0,
+    };
+    let system_mw = adapter_mw.unwrap_or(battery_mw);
+
+    Some(BatteryStats {
+        charge_percent: (current.max(0) * 100 / max).min(100) as u64,
+        charging,
+        external_power,
+        time_to_empty_min,
+        system_power_w: system_mw as f32 / 1000.0,
+    })
+}
+
+fn get_bool(dict: CFDictionaryRef, key: &str) -> Option<bool> {
+    let value = get_value(dict, key)?;
+    Some(unsafe { CFBooleanGetValue(value as CFBooleanRef) })
+}
@@ -1,4 +1,7 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
+use std::{fs, net::IpAddr, path::PathBuf};
 
 /// Command line options controlling sampling and layout.
 #[derive(Parser, Debug, Clone)]
@@ -12,19 +15,551 @@ pub struct Cli {
     #[arg(long, default_value_t = 2, value_name = "SECONDS")]
     pub interval: u64,
 
-    /// UI color (0-8) to match the classic asitop palette.
-    #[arg(long, default_value_t = 2)]
-    pub color: u8,
+    /// Sampling interval in milliseconds for sub-second sampling. Passed
+    /// straight to powermetrics; must be at least 50.
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        conflicts_with = "interval",
+        value_parser = parse_interval_ms
+    )]
+    pub interval_ms: Option<u64>,
 
-    /// Interval (in seconds) used for computing rolling averages.
+    /// Start at the requested interval, then lengthen it while parsing takes
+    /// a noticeable share of each sample and shorten it again when idle,
+    /// within `--min-interval-ms` and `--max-interval-ms`.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["from_file", "connect"])]
+    pub auto_interval: bool,
+
+    /// Shortest interval `--auto-interval` may pick (default: the requested
+    /// interval).
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        requires = "auto_interval",
+        value_parser = parse_interval_ms
+    )]
+    pub min_interval_ms: Option<u64>,
+
+    /// Longest interval `--auto-interval` may pick (default: 10x the
+    /// requested interval).
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        requires = "auto_interval",
+        value_parser = parse_interval_ms
+    )]
+    pub max_interval_ms: Option<u64>,
+
+    /// Accent color from the classic asitop palette, as 0-8 or a name
+    /// (default, red, green, yellow, blue, magenta, cyan, white, pink);
+    /// overrides the theme's accent.
+    #[arg(long, value_name = "0-8|NAME", value_parser = parse_color)]
+    pub color: Option<u8>,
+
+    /// Render without colors, using only glyphs and text. Also enabled by a
+    /// non-empty `NO_COLOR` environment variable.
+    #[arg(long, default_value_t = false)]
+    pub no_color: bool,
+
+    /// Named color theme.
+    #[arg(long, value_enum, default_value_t = ThemeName::Dark)]
+    pub theme: ThemeName,
+
+    /// JSON file overriding individual theme colors on top of `--theme`.
+    /// Colors are names ("lightblue"), 256-color indices ("208") or "#rrggbb".
+    #[arg(long, value_name = "PATH", value_parser = parse_theme_file)]
+    pub theme_file: Option<ThemeFile>,
+
+    /// Language used for UI labels.
+    #[arg(long, value_enum, default_value_t = Lang::En)]
+    pub lang: Lang,
+
+    /// Start with the diagnostics overlay visible (toggle with 'd').
+    #[arg(long, default_value_t = false)]
+    pub verbose: bool,
+
+    /// Interval (in seconds) used for computing rolling averages. The window is
+    /// rounded to the nearest whole number of samples, with a minimum of two.
     #[arg(long, default_value_t = 30, value_name = "SECONDS")]
     pub avg: u64,
 
+    /// Panels to show, top to bottom (comma-separated: processor, memory, io,
+    /// power).
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [Panel::Processor, Panel::Memory, Panel::Io, Panel::Power]
+    )]
+    pub panels: Vec<Panel>,
+
+    /// Don't poll IOKit disk statistics; the I/O panel shows network only.
+    #[arg(long, default_value_t = false)]
+    pub no_disk_io: bool,
+
+    /// Don't poll network interface counters; the I/O panel shows disk only.
+    #[arg(long, default_value_t = false)]
+    pub no_net_io: bool,
+
+    /// Average the E/P-cluster and GPU usage gauges over this many samples
+    /// (1 = off). Per-core bars are never smoothed.
+    #[arg(long, default_value_t = 1, value_name = "N")]
+    pub smooth: usize,
+
+    /// Redraw at this many frames per second, interpolating power readings
+    /// between the last two samples. Off by default since the in-between
+    /// values are estimates, not measurements.
+    #[arg(long, value_name = "FPS", value_parser = clap::value_parser!(u32).range(1..=60))]
+    pub refresh_rate: Option<u32>,
+
+    /// Where the GPU usage gauge reads from. `iokit` uses the AGX driver's
+    /// `Device Utilization %` for configs where powermetrics' idle ratio
+    /// lags or looks wrong.
+    #[arg(long, value_enum, default_value_t = GpuSource::Powermetrics)]
+    pub gpu_source: GpuSource,
+
+    /// Unit for clock frequencies in the cluster titles and core grid.
+    #[arg(long, value_enum, default_value_t = FreqUnit::Mhz)]
+    pub freq_unit: FreqUnit,
+
+    /// Unit for network rates in the I/O panel; disk rates stay in bytes.
+    #[arg(long, value_enum, default_value_t = NetUnit::Bytes)]
+    pub net_unit: NetUnit,
+
+    /// How "used" memory is counted: `available` is total minus inactive and
+    /// free pages; `activity` is app + wired + compressed, like Activity
+    /// Monitor's "Memory Used".
+    #[arg(long, value_enum, default_value_t = MemModel::Available)]
+    pub mem_model: MemModel,
+
+    /// What the CPU/GPU power percentages are relative to: the chip's
+    /// nominal TDP, or the highest draw observed this session.
+    #[arg(long, value_enum, default_value_t = PercentBasis::Tdp)]
+    pub percent_basis: PercentBasis,
+
     /// When true, render per-core information instead of compact gauges.
     #[arg(long, default_value_t = false)]
     pub show_cores: bool,
 
+    /// With `--show-cores`, add a legend line for the per-core load colors.
+    #[arg(long, default_value_t = false)]
+    pub legend: bool,
+
+    /// Maximum number of cores per row in the core grid (1-8). Fewer columns
+    /// are used when the panel is too narrow.
+    #[arg(
+        long,
+        default_value_t = 4,
+        value_name = "N",
+        value_parser = clap::value_parser!(u8).range(1..=8)
+    )]
+    pub core_columns: u8,
+
+    /// Draw with ASCII only (`#`/`-` bars, no block or arrow glyphs) for
+    /// terminals and fonts without them.
+    #[arg(long, default_value_t = false)]
+    pub ascii: bool,
+
+    /// Two characters used for the filled and empty parts of usage bars,
+    /// e.g. "=." (default "█░", or "#-" with `--ascii`).
+    #[arg(long, value_name = "FILLED_EMPTY", value_parser = parse_bar_chars)]
+    pub bar_chars: Option<BarChars>,
+
+    /// Use the flat accent color for the usage gauges instead of coloring by load.
+    #[arg(long, default_value_t = false)]
+    pub no_load_color: bool,
+
+    /// Four increasing core load percentages where the load color steps up,
+    /// e.g. "50,80,90,95" (default "30,50,70,90").
+    #[arg(long, value_name = "P1,P2,P3,P4", value_parser = parse_load_thresholds)]
+    pub load_thresholds: Option<[u64; 4]>,
+
     /// Restart powermetrics after this many samples (0 = never restart).
     #[arg(long, default_value_t = 0, value_name = "COUNT")]
     pub max_count: u64,
+
+    /// Time span (in seconds) covered by the power history sparkline, up to
+    /// a day.
+    #[arg(
+        long,
+        default_value_t = 240,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..=MAX_HISTORY_SECS)
+    )]
+    pub history: u64,
+
+    /// Pin the top of the power history sparkline to this many watts instead
+    /// of auto-scaling to the running peak, so curves stay comparable.
+    #[arg(long, value_name = "WATTS", value_parser = parse_power_scale)]
+    pub power_scale: Option<f32>,
+
+    /// Highlight the CPU, GPU and energy impact of one process (PID or name).
+    #[arg(long, value_name = "PID|NAME")]
+    pub watch_process: Option<String>,
+
+    /// Show the top energy-consuming processes panel at startup (toggle with 't').
+    #[arg(long, default_value_t = false)]
+    pub top_processes: bool,
+
+    /// Highlight the power panel when package power exceeds this many watts.
+    #[arg(long, value_name = "WATTS")]
+    pub alert_watts: Option<f32>,
+
+    /// Also post a macOS notification each time `--alert-watts` is crossed.
+    #[arg(long, default_value_t = false, requires = "alert_watts")]
+    pub alert_notify: bool,
+
+    /// Ring the terminal bell when thermal throttling starts.
+    #[arg(long, default_value_t = false)]
+    pub bell_on_throttle: bool,
+
+    /// File that 'D' appends diagnostic dumps to (default
+    /// ~/Library/Logs/asitop.log).
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Serve a browser dashboard at http://<host>:PORT/ and the live JSON
+    /// report at /metrics.json. There is no auth, so only loopback is
+    /// listened on unless `--web-bind` says otherwise.
+    #[arg(long, value_name = "PORT")]
+    pub web: Option<u16>,
+
+    /// Address `--web` listens on; `0.0.0.0` exposes it to the network.
+    #[arg(
+        long,
+        value_name = "ADDR",
+        default_value = "127.0.0.1",
+        requires = "web"
+    )]
+    pub web_bind: IpAddr,
+
+    /// Append every sample's CPU, GPU and package power to this file as a
+    /// plain time series for plotting after the run.
+    #[arg(long, value_name = "PATH")]
+    pub record: Option<PathBuf>,
+
+    /// Replay a saved powermetrics plist dump instead of running powermetrics.
+    /// Samples are shown one per interval; no root access is needed.
+    #[arg(long, value_name = "PATH")]
+    pub from_file: Option<PathBuf>,
+
+    /// Show another machine's asitop, started there with `--web PORT
+    /// --web-bind 0.0.0.0`, by polling HOST:PORT instead of running
+    /// powermetrics. No root access is needed; dropped connections are
+    /// retried every poll.
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "from_file")]
+    pub connect: Option<String>,
+
+    /// Start `--from-file` over at the end instead of exiting.
+    #[arg(long = "loop", default_value_t = false, requires = "from_file")]
+    pub loop_replay: bool,
+
+    /// Print a single self-updating status line instead of the full UI, e.g.
+    /// for a tmux status bar.
+    #[arg(long, default_value_t = false, conflicts_with = "output_once")]
+    pub oneline: bool,
+
+    /// Exit cleanly after running for this many seconds (at most a year).
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        conflicts_with = "output_once",
+        value_parser = clap::value_parser!(u64).range(1..=MAX_DURATION_SECS)
+    )]
+    pub duration: Option<u64>,
+
+    /// With `--duration`, print a plain-text power summary of the whole run
+    /// on exit.
+    #[arg(long, default_value_t = false, requires = "duration")]
+    pub summary: bool,
+
+    /// Stream every new sample to stdout as one JSON object per line
+    /// (NDJSON), e.g. for `jq` or a log collector.
+    #[arg(
+        long,
+        alias = "once-json-array",
+        default_value_t = false,
+        conflicts_with_all = ["output_once", "oneline"]
+    )]
+    pub ndjson: bool,
+
+    /// With `--ndjson`, emit one row per N samples holding their mean.
+    /// Smooths out sample noise at the cost of temporal resolution; the
+    /// live display cadence is unchanged.
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "ndjson",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub export_average: Option<u32>,
+
+    /// List every SMC key with its type and decoded value, then exit.
+    #[arg(long, default_value_t = false)]
+    pub list_sensors: bool,
+
+    /// Print a plain-text power summary after collecting samples, then exit.
+    #[arg(long, default_value_t = false)]
+    pub output_once: bool,
+
+    /// Number of samples collected by `--output-once`.
+    #[arg(long, default_value_t = 5, value_name = "COUNT")]
+    pub samples: u64,
+}
+
+/// Longest `--history`: a day.
+pub const MAX_HISTORY_SECS: u64 = 24 * 60 * 60;
+
+/// Longest `--duration`: a year.
+pub const MAX_DURATION_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// Shortest interval powermetrics can sample at reliably.
+pub const MIN_INTERVAL_MS: u64 = 50;
+
+fn parse_interval_ms(value: &str) -> Result<u64, String> {
+    let ms: u64 = value
+        .parse()
+        .map_err(|_| format!("`{value}` is not a whole number of milliseconds"))?;
+    if ms < MIN_INTERVAL_MS {
+        return Err(format!(
+            "{ms}ms is below the powermetrics minimum of {MIN_INTERVAL_MS}ms"
+        ));
+    }
+    Ok(ms)
+}
+
+/// Names accepted by `--color`, indexed by palette number.
+pub const COLOR_NAMES: [&str; 9] = [
+    "default", "red", "green", "yellow", "blue", "magenta", "cyan", "white", "pink",
+];
+
+fn parse_color(value: &str) -> Result<u8, String> {
+    let index = match value.parse::<u8>() {
+        Ok(number) => Some(number as usize).filter(|n| *n < COLOR_NAMES.len()),
+        Err(_) => COLOR_NAMES
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value)),
+    };
+    index.map(|n| n as u8).ok_or_else(|| {
+        format!(
+            "`{value}` is not a color; use 0-{} or one of {}",
+            COLOR_NAMES.len() - 1,
+            COLOR_NAMES.join(", ")
+        )
+    })
+}
+
+/// Per-element overrides from `--theme-file`; unset keys keep the preset's
+/// color and unknown keys are rejected.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeFile {
+    pub accent: Option<ThemeColor>,
+    pub text: Option<ThemeColor>,
+    pub muted: Option<ThemeColor>,
+    pub label: Option<ThemeColor>,
+    pub frequency: Option<ThemeColor>,
+    pub warning: Option<ThemeColor>,
+    pub critical: Option<ThemeColor>,
+    /// The five load colors, idle to saturated.
+    pub gradient: Option<[ThemeColor; 5]>,
+}
+
+/// A color written the way ratatui parses it.
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeColor(pub Color);
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value
+            .parse()
+            .map(ThemeColor)
+            .map_err(|_| serde::de::Error::custom(format!("unknown color `{value}`")))
+    }
+}
+
+fn parse_theme_file(path: &str) -> Result<ThemeFile, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    serde_json::from_str(&contents).map_err(|err| format!("{path}: {err}"))
+}
+
+/// Core load percentages where the load gradient steps to its next color.
+pub const DEFAULT_LOAD_THRESHOLDS: [u64; 4] = [30, 50, 70, 90];
+
+fn parse_load_thresholds(value: &str) -> Result<[u64; 4], String> {
+    let invalid = || format!("`{value}` must be four increasing percentages, e.g. 50,80,90,95");
+    let values = value
+        .split(',')
+        .map(|part| part.trim().parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let thresholds: [u64; 4] = values.try_into().map_err(|_| invalid())?;
+    let increasing = thresholds.windows(2).all(|pair| pair[0] < pair[1]);
+    if increasing && thresholds[3] <= 100 {
+        Ok(thresholds)
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Filled and empty characters of the usage bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarChars {
+    pub filled: char,
+    pub empty: char,
+}
+
+impl BarChars {
+    pub const UNICODE: Self = Self {
+        filled: '█',
+        empty: '░',
+    };
+    pub const ASCII: Self = Self {
+        filled: '#',
+        empty: '-',
+    };
+}
+
+fn parse_bar_chars(value: &str) -> Result<BarChars, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(filled), Some(empty), None) => Ok(BarChars { filled, empty }),
+        _ => Err(format!(
+            "`{value}` must be exactly two characters: filled then empty"
+        )),
+    }
+}
+
+fn parse_power_scale(value: &str) -> Result<f32, String> {
+    let watts: f32 = value
+        .parse()
+        .map_err(|_| format!("`{value}` is not a number of watts"))?;
+    if !watts.is_finite() || watts <= 0.0 {
+        return Err(format!("power scale must be above 0W, got {value}"));
+    }
+    Ok(watts)
+}
+
+impl Cli {
+    /// Effective sampling interval in milliseconds, from `--interval-ms` or
+    /// `--interval`.
+    pub fn sample_interval_ms(&self) -> u64 {
+        self.interval_ms
+            .unwrap_or_else(|| self.interval.max(1) * 1000)
+    }
+
+    /// `--auto-interval` bounds in milliseconds, ordered so that a swapped
+    /// pair still gives a usable range.
+    pub fn auto_interval_range(&self) -> (u64, u64) {
+        let requested = self.sample_interval_ms();
+        let min = self.min_interval_ms.unwrap_or(requested);
+        let max = self.max_interval_ms.unwrap_or(requested * 10);
+        (min.min(max), min.max(max))
+    }
+
+    /// Whether colors are off, via `--no-color` or `NO_COLOR`
+    /// (<https://no-color.org>).
+    pub fn monochrome(&self) -> bool {
+        self.no_color || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    }
+
+    /// `--panels` with repeats removed, keeping the first occurrence. The I/O
+    /// panel is dropped when both of its sources are disabled.
+    pub fn panel_order(&self) -> Vec<Panel> {
+        let io_disabled = self.no_disk_io && self.no_net_io;
+        let mut order = Vec::new();
+        for panel in &self.panels {
+            if *panel == Panel::Io && io_disabled {
+                continue;
+            }
+            if !order.contains(panel) {
+                order.push(*panel);
+            }
+        }
+        order
+    }
+
+    /// Thresholds from `--load-thresholds`, else [`DEFAULT_LOAD_THRESHOLDS`].
+    pub fn load_thresholds(&self) -> [u64; 4] {
+        self.load_thresholds.unwrap_or(DEFAULT_LOAD_THRESHOLDS)
+    }
+
+    /// Bar characters from `--bar-chars`, else the `--ascii` or Unicode set.
+    pub fn bar_chars(&self) -> BarChars {
+        self.bar_chars.unwrap_or(if self.ascii {
+            BarChars::ASCII
+        } else {
+            BarChars::UNICODE
+        })
+    }
+
+    /// Whether powermetrics has to run the per-process `tasks` sampler.
+    pub fn needs_tasks(&self) -> bool {
+        self.watch_process.is_some() || self.top_processes
+    }
+}
+
+/// Main UI sections selectable with `--panels`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Processor,
+    Memory,
+    Io,
+    Power,
+}
+
+/// Source of the GPU utilization figure.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuSource {
+    Powermetrics,
+    Iokit,
+}
+
+/// Display unit for frequencies, which are always stored in MHz.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreqUnit {
+    Mhz,
+    Ghz,
+}
+
+/// Display unit for network rates: `bytes` as MB/s like the disk rates, or
+/// `bits` as Mbps like link speeds.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetUnit {
+    Bytes,
+    Bits,
+}
+
+/// Definition of "used" memory.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemModel {
+    /// total - (inactive + free): memory not immediately reclaimable.
+    Available,
+    /// app (internal - purgeable) + wired + compressed, as Activity Monitor.
+    Activity,
+}
+
+/// Reference for the power percentages in the power panel.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PercentBasis {
+    Tdp,
+    Peak,
+}
+
+/// Built-in color theme presets.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeName {
+    Dark,
+    Light,
+    Solarized,
+    Mono,
+}
+
+/// UI label languages.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Zh,
 }
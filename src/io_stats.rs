@@ -1,35 +1,82 @@
+use crate::iokit::{
+    IOIteratorNext, IOObjectRelease, IORegistryEntryCreateCFProperties,
+    IORegistryEntryCreateCFProperty, IORegistryEntryGetChildEntry,
+    IORegistryEntryGetRegistryEntryID, IOServiceGetMatchingServices, IOServiceMatching, cf_string,
+    get_i64, get_value, io_iterator_t, io_registry_entry_t,
+};
 use core_foundation_sys::{
-    base::{Boolean, CFAllocatorRef, CFRelease, CFTypeRef},
+    base::{CFIndex, CFRelease, CFTypeRef},
     dictionary::{CFDictionaryRef, CFMutableDictionaryRef},
-    number::{CFNumberRef, CFNumberType, kCFNumberSInt64Type},
-    string::{CFStringEncoding, CFStringRef, kCFStringEncodingUTF8},
+    string::{CFStringEncoding, CFStringGetCString, CFStringRef, kCFStringEncodingUTF8},
 };
 use libc::{
-    self, AF_LINK, IFF_LOOPBACK, IFF_UP, KERN_SUCCESS, c_char, c_void, freeifaddrs, getifaddrs,
-    if_data, ifaddrs, mach_port_t,
+    self, AF_LINK, IFF_LOOPBACK, IFF_UP, KERN_SUCCESS, c_char, freeifaddrs, getifaddrs, if_data,
+    ifaddrs,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    ptr,
+    time::{Duration, Instant},
 };
-use std::{ffi::CString, ptr, time::{Duration, Instant}};
 
 const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct IoStats {
     pub net_in_mbps: f32,
     pub net_out_mbps: f32,
     pub disk_read_mbps: f32,
     pub disk_write_mbps: f32,
+    pub net_in_pps: f32,
+    pub net_out_pps: f32,
+    /// Bytes received since launch.
+    pub net_in_total: u64,
+    /// Bytes sent since launch.
+    pub net_out_total: u64,
+    /// Bytes read from disk since launch.
+    pub disk_read_total: u64,
+    /// Bytes written to disk since launch.
+    pub disk_write_total: u64,
+}
+
+/// Raw `if_data` counters of one interface. They are 32-bit on macOS and
+/// wrap every 4 GiB, about every 34 s on a saturated 1 Gbps link.
+#[derive(Debug, Clone, Copy, Default)]
+struct NetCounters {
+    in_bytes: u32,
+    out_bytes: u32,
+    in_packets: u32,
+    out_packets: u32,
+}
+
+/// Traffic between two samples, summed over all non-loopback links.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct NetTraffic {
+    in_bytes: u64,
+    out_bytes: u64,
+    in_packets: u64,
+    out_packets: u64,
 }
 
 pub struct IoSampler {
-    last_net: Option<(u64, u64)>,
-    last_disk: Option<(u64, u64)>,
+    /// Skipped sources are never queried and stay at zero.
+    net_enabled: bool,
+    disk_enabled: bool,
+    /// Interface counters keyed by interface name.
+    last_net: Option<HashMap<String, NetCounters>>,
+    /// Read/write byte counters per disk, keyed by BSD name.
+    last_disk: Option<HashMap<String, (u64, u64)>>,
     last_instant: Option<Instant>,
     current: IoStats,
 }
 
 impl IoSampler {
-    pub fn new() -> Self {
+    pub fn new(net_enabled: bool, disk_enabled: bool) -> Self {
         Self {
+            net_enabled,
+            disk_enabled,
             last_net: None,
             last_disk: None,
             last_instant: None,
@@ -47,8 +94,16 @@ impl IoSampler {
             }
         }
 
-        let net_totals = read_network_counters();
-        let disk_totals = read_disk_counters();
+        let net_totals = if self.net_enabled {
+            read_network_counters()
+        } else {
+            None
+        };
+        let disk_totals = if self.disk_enabled {
+            read_disk_counters()
+        } else {
+            None
+        };
 
         if self.last_instant.is_none() {
             self.last_instant = Some(now);
@@ -63,20 +118,43 @@ impl IoSampler {
             .as_secs_f64()
             .max(0.001);
 
-        if let Some((in_bytes, out_bytes)) = net_totals {
-            if let Some((prev_in, prev_out)) = self.last_net {
-                self.current.net_in_mbps = rate_from_delta(in_bytes, prev_in, delta);
-                self.current.net_out_mbps = rate_from_delta(out_bytes, prev_out, delta);
+        if let Some(interfaces) = net_totals {
+            if let Some(previous) = &self.last_net {
+                let traffic = net_traffic(&interfaces, previous);
+                self.current.net_in_mbps = rate_from_delta(traffic.in_bytes, 0, delta);
+                self.current.net_out_mbps = rate_from_delta(traffic.out_bytes, 0, delta);
+                self.current.net_in_total += traffic.in_bytes;
+                self.current.net_out_total += traffic.out_bytes;
+                self.current.net_in_pps = packet_rate(traffic.in_packets, 0, delta);
+                self.current.net_out_pps = packet_rate(traffic.out_packets, 0, delta);
             }
-            self.last_net = Some((in_bytes, out_bytes));
+            self.last_net = Some(interfaces);
         }
 
-        if let Some((read_bytes, write_bytes)) = disk_totals {
-            if let Some((prev_read, prev_write)) = self.last_disk {
-                self.current.disk_read_mbps = rate_from_delta(read_bytes, prev_read, delta);
-                self.current.disk_write_mbps = rate_from_delta(write_bytes, prev_write, delta);
+        if let Some(disks) = disk_totals {
+            if let Some(previous) = &self.last_disk {
+                // Compare each disk only with itself so services enumerating in
+                // a different order cannot make the totals jump. Disks that
+                // appeared since the last sample only set a baseline; ones that
+                // vanished simply drop out.
+                let (read, written) = disks
+                    .iter()
+                    .filter_map(|(name, (read, write))| {
+                        let (prev_read, prev_write) = previous.get(name)?;
+                        Some((
+                            positive_delta(*read, *prev_read),
+                            positive_delta(*write, *prev_write),
+                        ))
+                    })
+                    .fold((0u64, 0u64), |(r, w), (dr, dw)| {
+                        (r.saturating_add(dr), w.saturating_add(dw))
+                    });
+                self.current.disk_read_mbps = rate_from_delta(read, 0, delta);
+                self.current.disk_write_mbps = rate_from_delta(written, 0, delta);
+                self.current.disk_read_total += read;
+                self.current.disk_write_total += written;
             }
-            self.last_disk = Some((read_bytes, write_bytes));
+            self.last_disk = Some(disks);
         }
 
         self.last_instant = Some(now);
@@ -84,6 +162,8 @@ impl IoSampler {
     }
 }
 
+/// MiB/s between two byte counters. Equal counters, a counter that went
+/// backwards (reset or wraparound) and a non-positive interval all read 0.
 fn rate_from_delta(current: u64, previous: u64, delta_secs: f64) -> f32 {
     if current <= previous || delta_secs <= 0.0 {
         0.0
@@ -93,7 +173,41 @@ fn rate_from_delta(current: u64, previous: u64, delta_secs: f64) -> f32 {
     }
 }
 
-fn read_network_counters() -> Option<(u64, u64)> {
+fn packet_rate(current: u64, previous: u64, delta_secs: f64) -> f32 {
+    if current <= previous || delta_secs <= 0.0 {
+        0.0
+    } else {
+        ((current - previous) as f64 / delta_secs) as f32
+    }
+}
+
+/// Counter resets are treated as no traffic rather than a negative delta.
+fn positive_delta(current: u64, previous: u64) -> u64 {
+    current.saturating_sub(previous)
+}
+
+/// Compare each interface only with itself, like the disks. The 32-bit
+/// counters are differenced modulo 2^32 so a wrap between samples still
+/// counts the traffic. Interfaces that appeared since the last sample only
+/// set a baseline.
+fn net_traffic(
+    current: &HashMap<String, NetCounters>,
+    previous: &HashMap<String, NetCounters>,
+) -> NetTraffic {
+    let mut traffic = NetTraffic::default();
+    for (name, now) in current {
+        let Some(before) = previous.get(name) else {
+            continue;
+        };
+        traffic.in_bytes += now.in_bytes.wrapping_sub(before.in_bytes) as u64;
+        traffic.out_bytes += now.out_bytes.wrapping_sub(before.out_bytes) as u64;
+        traffic.in_packets += now.in_packets.wrapping_sub(before.in_packets) as u64;
+        traffic.out_packets += now.out_packets.wrapping_sub(before.out_packets) as u64;
+    }
+    traffic
+}
+
+fn read_network_counters() -> Option<HashMap<String, NetCounters>> {
     // SAFETY: We use getifaddrs/freeifaddrs correctly:
     // 1. ifap is initialized to null before getifaddrs
     // 2. We check both return value and null pointer
@@ -108,8 +222,7 @@ fn read_network_counters() -> Option<(u64, u64)> {
             return None;
         }
         
-        let mut total_in = 0u64;
-        let mut total_out = 0u64;
+        let mut interfaces = HashMap::new();
         let mut cursor = ifap;
         
         // Limit iterations to prevent infinite loops from corrupted data
@@ -130,9 +243,21 @@ fn read_network_counters() -> Option<(u64, u64)> {
                         let data_ptr = iface.ifa_data as *const if_data;
                         if !data_ptr.is_null() {
                             // Use as_ref for safe optional dereference
-                            if let Some(data) = data_ptr.as_ref() {
-                                total_in = total_in.saturating_add(data.ifi_ibytes as u64);
-                                total_out = total_out.saturating_add(data.ifi_obytes as u64);
+                            if let Some(data) = data_ptr.as_ref()
+                                && !iface.ifa_name.is_null()
+                            {
+                                let name = CStr::from_ptr(iface.ifa_name)
+                                    .to_string_lossy()
+                                    .into_owned();
+                                interfaces.insert(
+                                    name,
+                                    NetCounters {
+                                        in_bytes: data.ifi_ibytes,
+                                        out_bytes: data.ifi_obytes,
+                                        in_packets: data.ifi_ipackets,
+                                        out_packets: data.ifi_opackets,
+                                    },
+                                );
                             }
                         }
                     }
@@ -142,13 +267,13 @@ fn read_network_counters() -> Option<(u64, u64)> {
         }
         
         freeifaddrs(ifap);
-        Some((total_in, total_out))
+        Some(interfaces)
     }
 }
 
-fn read_disk_counters() -> Option<(u64, u64)> {
+fn read_disk_counters() -> Option<HashMap<String, (u64, u64)>> {
     unsafe {
-        let matching = IOServiceMatching(b"IOBlockStorageDriver\0".as_ptr() as *const c_char);
+        let matching = IOServiceMatching(c"IOBlockStorageDriver".as_ptr());
         if matching.is_null() {
             return None;
         }
@@ -160,26 +285,68 @@ fn read_disk_counters() -> Option<(u64, u64)> {
             }
             return None;
         }
-        let mut total_read = 0u64;
-        let mut total_write = 0u64;
+        let mut disks = HashMap::new();
         loop {
             let entry = IOIteratorNext(iterator);
             if entry == 0 {
                 break;
             }
-            if let Some((read, write)) = read_entry_bytes(entry) {
-                total_read = total_read.saturating_add(read);
-                total_write = total_write.saturating_add(write);
+            if let Some(bytes) = read_entry_bytes(entry) {
+                disks.insert(disk_name(entry), bytes);
             }
             IOObjectRelease(entry);
         }
         if iterator != 0 {
             IOObjectRelease(iterator);
         }
-        Some((total_read, total_write))
+        Some(disks)
     }
 }
 
+/// BSD name (e.g. "disk0") of the media below a block storage driver, or its
+/// registry entry ID when it has none.
+fn disk_name(entry: io_registry_entry_t) -> String {
+    unsafe {
+        let mut media: io_registry_entry_t = 0;
+        if IORegistryEntryGetChildEntry(entry, c"IOService".as_ptr(), &mut media) == KERN_SUCCESS {
+            let name = cf_string("BSD Name").and_then(|key| {
+                let value = IORegistryEntryCreateCFProperty(media, key, ptr::null(), 0);
+                CFRelease(key as CFTypeRef);
+                if value.is_null() {
+                    return None;
+                }
+                let name = string_from_cf(value as CFStringRef);
+                CFRelease(value);
+                name
+            });
+            IOObjectRelease(media);
+            if let Some(name) = name {
+                return name;
+            }
+        }
+        let mut id = 0u64;
+        IORegistryEntryGetRegistryEntryID(entry, &mut id);
+        format!("entry-{id}")
+    }
+}
+
+fn string_from_cf(value: CFStringRef) -> Option<String> {
+    let mut buffer = [0 as c_char; 128];
+    let ok = unsafe {
+        CFStringGetCString(
+            value,
+            buffer.as_mut_ptr(),
+            buffer.len() as CFIndex,
+            kCFStringEncodingUTF8 as CFStringEncoding,
+        )
+    };
+    if ok == 0 {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
 fn read_entry_bytes(entry: io_registry_entry_t) -> Option<(u64, u64)> {
     unsafe {
         let mut properties: CFMutableDictionaryRef = ptr::null_mut();
@@ -199,97 +366,56 @@ fn read_entry_bytes(entry: io_registry_entry_t) -> Option<(u64, u64)> {
 }
 
 fn get_dict_value(dict: CFDictionaryRef, key: &str) -> Option<CFDictionaryRef> {
-    let cf_key = cf_string(key)?;
-    let mut value: *const c_void = ptr::null();
-    let success =
-        unsafe { CFDictionaryGetValueIfPresent(dict, cf_key as *const c_void, &mut value) };
-    unsafe {
-        CFRelease(cf_key as CFTypeRef);
-    }
-    if success == 0 || value.is_null() {
-        None
-    } else {
-        Some(value as CFDictionaryRef)
-    }
+    get_value(dict, key).map(|value| value as CFDictionaryRef)
 }
 
 fn get_number(dict: CFDictionaryRef, key: &str) -> Option<u64> {
-    let cf_key = cf_string(key)?;
-    let mut value: *const c_void = ptr::null();
-    let success =
-        unsafe { CFDictionaryGetValueIfPresent(dict, cf_key as *const c_void, &mut value) };
-    unsafe {
-        CFRelease(cf_key as CFTypeRef);
-    }
-    if success == 0 || value.is_null() {
-        return None;
-    }
-    let mut raw: i64 = 0;
-    let ok = unsafe {
-        CFNumberGetValue(
-            value as CFNumberRef,
-            kCFNumberSInt64Type as CFNumberType,
-            &mut raw as *mut _ as *mut c_void,
-        )
-    };
-    if ok == 0 {
-        return None;
-    }
-    Some(raw.max(0) as u64)
+    get_i64(dict, key).map(|raw| raw.max(0) as u64)
 }
 
-fn cf_string(value: &str) -> Option<CFStringRef> {
-    let cstring = CString::new(value).ok()?;
-    let cf = unsafe {
-        CFStringCreateWithCString(
-            ptr::null(),
-            cstring.as_ptr(),
-            kCFStringEncodingUTF8 as CFStringEncoding,
-        )
-    };
-    if cf.is_null() { None } else { Some(cf) }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[allow(non_camel_case_types)]
-type io_object_t = mach_port_t;
-#[allow(non_camel_case_types)]
-type io_iterator_t = io_object_t;
-#[allow(non_camel_case_types)]
-type io_registry_entry_t = io_object_t;
+    #[test]
+    fn rate_from_equal_counters_is_zero() {
+        assert_eq!(rate_from_delta(4096, 4096, 1.0), 0.0);
+    }
 
-#[link(name = "IOKit", kind = "framework")]
-unsafe extern "C" {
-    fn IOServiceMatching(name: *const c_char) -> CFMutableDictionaryRef;
-    fn IOServiceGetMatchingServices(
-        master_port: mach_port_t,
-        matching: CFMutableDictionaryRef,
-        existing: *mut io_iterator_t,
-    ) -> libc::kern_return_t;
-    fn IOIteratorNext(iterator: io_iterator_t) -> io_object_t;
-    fn IOObjectRelease(object: io_object_t) -> libc::kern_return_t;
-    fn IORegistryEntryCreateCFProperties(
-        entry: io_registry_entry_t,
-        properties: *mut CFMutableDictionaryRef,
-        allocator: CFAllocatorRef,
-        options: u32,
-    ) -> libc::kern_return_t;
-}
+    #[test]
+    fn rate_from_wrapped_counter_is_zero() {
+        assert_eq!(rate_from_delta(10, u64::MAX - 10, 1.0), 0.0);
+    }
 
-#[link(name = "CoreFoundation", kind = "framework")]
-unsafe extern "C" {
-    fn CFStringCreateWithCString(
-        alloc: CFAllocatorRef,
-        c_str: *const c_char,
-        encoding: CFStringEncoding,
-    ) -> CFStringRef;
-    fn CFDictionaryGetValueIfPresent(
-        dict: CFDictionaryRef,
-        key: *const c_void,
-        value: *mut *const c_void,
-    ) -> Boolean;
-    fn CFNumberGetValue(
-        number: CFNumberRef,
-        the_type: CFNumberType,
-        value_ptr: *mut c_void,
-    ) -> Boolean;
+    #[test]
+    fn net_traffic_survives_counter_wrap() {
+        let counters = |bytes: u32, packets: u32| NetCounters {
+            in_bytes: bytes,
+            out_bytes: bytes,
+            in_packets: packets,
+            out_packets: packets,
+        };
+        let previous = HashMap::from([
+            ("en0".to_string(), counters(u32::MAX - 99, 10)),
+            ("en1".to_string(), counters(1000, u32::MAX)),
+        ]);
+        let current = HashMap::from([
+            ("en0".to_string(), counters(400, 20)),
+            ("en1".to_string(), counters(1500, 4)),
+            // New since the last sample: baseline only.
+            ("en5".to_string(), counters(9000, 90)),
+        ]);
+        let traffic = net_traffic(&current, &previous);
+        assert_eq!(traffic.in_bytes, 500 + 500);
+        assert_eq!(traffic.out_bytes, 1000);
+        assert_eq!(traffic.in_packets, 10 + 5);
+        assert_eq!(traffic.out_packets, 15);
+    }
+
+    #[test]
+    fn rate_from_known_delta() {
+        // 3 MiB over half a second.
+        assert_eq!(rate_from_delta(3 * 1024 * 1024, 0, 0.5), 6.0);
+        assert_eq!(rate_from_delta(1024 * 1024, 0, 0.0), 0.0);
+    }
 }
@@ -0,0 +1,134 @@
+use core_foundation_sys::{
+    base::{CFAllocatorRef, CFRelease, CFTypeRef},
+    dictionary::{CFDictionaryGetValueIfPresent, CFDictionaryRef, CFMutableDictionaryRef},
+    number::{CFNumberGetValue, CFNumberRef, CFNumberType, kCFNumberSInt64Type},
+    string::{CFStringCreateWithCString, CFStringEncoding, CFStringRef, kCFStringEncodingUTF8},
+};
+use libc::{KERN_SUCCESS, c_char, c_void, kern_return_t, mach_port_t};
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+};
+
+#[allow(non_camel_case_types)]
+pub type io_object_t = mach_port_t;
+#[allow(non_camel_case_types)]
+pub type io_iterator_t = io_object_t;
+#[allow(non_camel_case_types)]
+pub type io_registry_entry_t = io_object_t;
+#[allow(non_camel_case_types)]
+pub type io_connect_t = mach_port_t;
+
+/// Properties of the first service matching `class`. The caller owns the
+/// returned dictionary and must `CFRelease` it.
+pub fn service_properties(class: &CStr) -> Option<CFMutableDictionaryRef> {
+    unsafe {
+        let matching = IOServiceMatching(class.as_ptr());
+        if matching.is_null() {
+            return None;
+        }
+        let service = IOServiceGetMatchingService(0, matching);
+        if service == 0 {
+            return None;
+        }
+        let mut properties: CFMutableDictionaryRef = ptr::null_mut();
+        let result = IORegistryEntryCreateCFProperties(service, &mut properties, ptr::null(), 0);
+        IOObjectRelease(service);
+        if result != KERN_SUCCESS || properties.is_null() {
+            return None;
+        }
+        Some(properties)
+    }
+}
+
+pub fn get_value(dict: CFDictionaryRef, key: &str) -> Option<*const c_void> {
+    let cf_key = cf_string(key)?;
+    let mut value: *const c_void = ptr::null();
+    let success =
+        unsafe { CFDictionaryGetValueIfPresent(dict, cf_key as *const c_void, &mut value) };
+    unsafe {
+        CFRelease(cf_key as CFTypeRef);
+    }
+    if success == 0 || value.is_null() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+pub fn get_i64(dict: CFDictionaryRef, key: &str) -> Option<i64> {
+    let value = get_value(dict, key)?;
+    let mut raw: i64 = 0;
+    let ok = unsafe {
+        CFNumberGetValue(
+            value as CFNumberRef,
+            kCFNumberSInt64Type as CFNumberType,
+            &mut raw as *mut _ as *mut c_void,
+        )
+    };
+    ok.then_some(raw)
+}
+
+pub fn cf_string(value: &str) -> Option<CFStringRef> {
+    let cstring = CString::new(value).ok()?;
+    let cf = unsafe {
+        CFStringCreateWithCString(
+            ptr::null(),
+            cstring.as_ptr(),
+            kCFStringEncodingUTF8 as CFStringEncoding,
+        )
+    };
+    if cf.is_null() { None } else { Some(cf) }
+}
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    pub fn IOServiceMatching(name: *const c_char) -> CFMutableDictionaryRef;
+    pub fn IOServiceGetMatchingService(
+        master_port: mach_port_t,
+        matching: CFMutableDictionaryRef,
+    ) -> io_object_t;
+    pub fn IOServiceGetMatchingServices(
+        master_port: mach_port_t,
+        matching: CFMutableDictionaryRef,
+        existing: *mut io_iterator_t,
+    ) -> kern_return_t;
+    pub fn IOIteratorNext(iterator: io_iterator_t) -> io_object_t;
+    pub fn IOObjectRelease(object: io_object_t) -> kern_return_t;
+    pub fn IORegistryEntryGetChildEntry(
+        entry: io_registry_entry_t,
+        plane: *const c_char,
+        child: *mut io_registry_entry_t,
+    ) -> kern_return_t;
+    pub fn IORegistryEntryCreateCFProperty(
+        entry: io_registry_entry_t,
+        key: CFStringRef,
+        allocator: CFAllocatorRef,
+        options: u32,
+    ) -> CFTypeRef;
+    pub fn IORegistryEntryGetRegistryEntryID(
+        entry: io_registry_entry_t,
+        entry_id: *mut u64,
+    ) -> kern_return_t;
+    pub fn IORegistryEntryCreateCFProperties(
+        entry: io_registry_entry_t,
+        properties: *mut CFMutableDictionaryRef,
+        allocator: CFAllocatorRef,
+        options: u32,
+    ) -> kern_return_t;
+    pub fn IOServiceOpen(
+        service: io_object_t,
+        owning_task: mach_port_t,
+        connect_type: u32,
+        connect: *mut io_connect_t,
+    ) -> kern_return_t;
+    pub fn IOServiceClose(connect: io_connect_t) -> kern_return_t;
+    pub fn IOConnectCallStructMethod(
+        connection: io_connect_t,
+        selector: u32,
+        input: *const c_void,
+        input_size: usize,
+        output: *mut c_void,
+        output_size: *mut usize,
+    ) -> kern_return_t;
+}
@@ -1,36 +1,62 @@
+mod accelerator;
+mod battery;
 mod config;
 mod io_stats;
+mod iokit;
 mod memory;
 mod powermetrics;
+mod record;
+mod smc;
 mod soc;
+mod strings;
+mod summary;
 mod thermal;
 mod ui;
+mod web;
 
+use accelerator::{AcceleratorStats, read_accelerator};
 use anyhow::{Context, Result};
+use battery::{BatteryStats, read_battery};
 use clap::Parser;
-use config::Cli;
+use config::{COLOR_NAMES, Cli, GpuSource, Panel};
 use crossterm::{
-    cursor::Show,
+    cursor::{Hide, Show},
     event::{self, Event, KeyCode, KeyModifiers},
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    },
 };
 use io_stats::{IoSampler, IoStats};
 use memory::{MemoryReader, MemoryStats};
 use powermetrics::{
     CpuMetrics, GpuMetrics, History, PowermetricsReader, PowermetricsReading, RollingAverage,
-    cleanup_powermetrics_files, new_timecode, run_powermetrics,
+    TaskMetrics, cleanup_powermetrics_files, new_timecode, powermetrics_path, preflight,
+    rolling_window, run_powermetrics,
 };
 use ratatui::{Terminal, backend::CrosstermBackend, prelude::*};
-use soc::SocInfo;
+use record::PowerRecorder;
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+use soc::{SocInfo, is_translated};
 use std::{
-    io::{self, stdout},
-    process::Child,
+    cell::Cell,
+    fs,
+    io::{self, IsTerminal, Write, stdout},
+    net::SocketAddr,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use strings::Strings;
+use summary::Summary;
 use thermal::{ThermalLevel, read_warning_level};
-use ui::{PowerSnapshot, UiSnapshot};
+use ui::{Diagnostics, FreqHistory, PowerSnapshot, Theme, UiSnapshot, WatchedProcess};
+use web::{HostStats, WebServer};
 
 /// RAII wrapper for powermetrics child process.
 /// Ensures the child process is killed and waited on when dropped,
@@ -44,15 +70,21 @@ impl PowermetricsGuard {
         Self { child: Some(child) }
     }
 
+    /// A guard with no process, used when replaying `--from-file`.
+    fn detached() -> Self {
+        Self { child: None }
+    }
+
     /// Kill and restart the process with a new timecode
-    fn restart(&mut self, timecode: &str, interval_ms: u64) -> Result<()> {
+    fn restart(&mut self, timecode: &str, interval_ms: u64, with_tasks: bool) -> Result<()> {
         // Kill existing process first
         if let Some(ref mut child) = self.child {
             child.kill().ok();
             child.wait().ok();
         }
+        cleanup_powermetrics_files().ok();
         // Start new process
-        self.child = Some(run_powermetrics(timecode, interval_ms)?);
+        self.child = Some(run_powermetrics(timecode, interval_ms, with_tasks)?);
         Ok(())
     }
 
@@ -66,6 +98,43 @@ impl PowermetricsGuard {
     }
 }
 
+/// A replacement powermetrics started by `--max-count` or `--auto-interval`.
+/// It runs alongside the current one, which keeps feeding the display until
+/// the replacement produces its first reading, so the restart leaves no gap.
+struct Handover {
+    guard: PowermetricsGuard,
+    timecode: String,
+    reader: PowermetricsReader,
+    interval_ms: u64,
+    started: Instant,
+}
+
+impl Handover {
+    /// `None` for replays and remote streams, which have no process to
+    /// restart.
+    fn start(state: &AppState, interval_ms: u64) -> Result<Option<Self>> {
+        if state.config.from_file.is_some() || state.config.connect.is_some() {
+            return Ok(None);
+        }
+        let timecode = new_timecode();
+        let child = run_powermetrics(&timecode, interval_ms, state.tasks_sampler)?;
+        Ok(Some(Self {
+            guard: PowermetricsGuard::new(child),
+            reader: PowermetricsReader::new(&timecode),
+            timecode,
+            interval_ms,
+            started: Instant::now(),
+        }))
+    }
+
+    /// Stop the replacement process and remove its timecoded file.
+    fn abandon(self) {
+        let path = powermetrics_path(&self.timecode);
+        drop(self.guard);
+        fs::remove_file(path).ok();
+    }
+}
+
 impl Drop for PowermetricsGuard {
     fn drop(&mut self) {
         // Ensure cleanup on panic or early return
@@ -79,33 +148,136 @@ impl Drop for PowermetricsGuard {
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    println!("\nASITOP_IN_RUST - An improved and refactored version of ASITOP, a performance monitoring CLI tool for Apple Silicon");
-    println!("Original ASITOP https://github.com/tlkh/asitop");
-    println!("Get help at https://github.com/Aeovy/asitop_InRust\n");
-    println!("[1/3] Detecting SoC and preparing powermetrics\n");
+    if cli.list_sensors {
+        return smc::print_sensors();
+    }
+    install_panic_hook();
+    let shutdown = register_shutdown_signals()?;
+    // `--ndjson` owns stdout, so progress messages go to stderr there.
+    let progress = |line: &str| {
+        if cli.ndjson {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    };
+    progress(
+        "\nASITOP_IN_RUST - An improved and refactored version of ASITOP, a performance monitoring CLI tool for Apple Silicon",
+    );
+    progress("Original ASITOP https://github.com/tlkh/asitop");
+    progress("Get help at https://github.com/Aeovy/asitop_InRust\n");
+    progress("[1/3] Detecting SoC and preparing powermetrics\n");
 
+    if is_translated() {
+        eprintln!(
+            "warning: asitop is running under Rosetta; sysctl and IOKit readings may be off. Install or build a native arm64 binary."
+        );
+    }
     let soc = SocInfo::detect();
-    let mut memory_reader = MemoryReader::new();
-    let mut io_sampler = IoSampler::new();
+    let mut memory_reader = MemoryReader::new(cli.mem_model);
+    let mut io_sampler = IoSampler::new(!cli.no_net_io, !cli.no_disk_io);
     cleanup_powermetrics_files().ok();
 
-    println!("[2/3] Starting powermetrics process\n");
     let mut timecode = new_timecode();
-    let child =
-        run_powermetrics(&timecode, cli.interval * 1000).context("failed to spawn powermetrics")?;
-    // Wrap child in RAII guard to ensure cleanup on panic or early return
-    let mut guard = PowermetricsGuard::new(child);
-    let mut pm_reader = PowermetricsReader::new(&timecode);
-    println!("[3/3] Waiting for first reading...\n");
-
-    let first_reading = wait_for_reading(&mut pm_reader, Duration::from_millis(100))
-        .context("powermetrics never produced a reading")?;
+    let (mut guard, mut pm_reader) = if let Some(addr) = &cli.connect {
+        progress(&format!("[2/3] Connecting to {addr}\n"));
+        let interval = Duration::from_millis(cli.sample_interval_ms());
+        (
+            PowermetricsGuard::detached(),
+            PowermetricsReader::connect(addr, interval),
+        )
+    } else if let Some(path) = &cli.from_file {
+        progress(&format!("[2/3] Loading {}\n", path.display()));
+        let interval = Duration::from_millis(cli.sample_interval_ms());
+        let reader = PowermetricsReader::from_file(path, interval, cli.loop_replay)?;
+        (PowermetricsGuard::detached(), reader)
+    } else {
+        progress("[2/3] Starting powermetrics process\n");
+        preflight()?;
+        let child = run_powermetrics(&timecode, cli.sample_interval_ms(), cli.needs_tasks())
+            .context("failed to spawn powermetrics")?;
+        // Wrap child in RAII guard to ensure cleanup on panic or early return
+        (
+            PowermetricsGuard::new(child),
+            PowermetricsReader::new(&timecode),
+        )
+    };
+    progress("[3/3] Waiting for first reading...\n");
+
+    if let Some(ThermalLevel::Unknown(raw)) =
+        read_warning_level().filter(|level| level.is_unmapped())
+    {
+        eprintln!(
+            "note: unmapped thermal warning level {raw}; please report it with your macOS version"
+        );
+    }
 
     let mut state = AppState::new(cli.clone(), soc, &mut memory_reader);
-    state.apply_reading(first_reading, &mut io_sampler);
-    state.memory_stats = memory_reader.read();
+    if let Some(path) = &cli.record {
+        state.recorder = Some(PowerRecorder::create(path)?);
+    }
+    if let Some(port) = cli.web {
+        state.web = Some(WebServer::start(cli.web_bind, port)?);
+        progress(&format!(
+            "Serving the dashboard at http://{}/\n",
+            SocketAddr::new(cli.web_bind, port)
+        ));
+    }
+    let interactive = !cli.output_once && !cli.oneline && !cli.ndjson && stdout().is_terminal();
+    // The full-screen UI shows its own waiting state; the plain-text modes
+    // need a reading before they can print anything.
+    if !interactive {
+        let first_reading = wait_for_reading(
+            &shutdown,
+            &mut pm_reader,
+            Duration::from_millis(cli.sample_interval_ms()),
+        )
+        .context("powermetrics never produced a reading")?;
+        state.memory_stats = memory_reader.read();
+        state.apply_reading(first_reading, &mut io_sampler);
+    }
+
+    if cli.output_once {
+        let result = run_output_once(
+            &shutdown,
+            &mut state,
+            &mut pm_reader,
+            &mut memory_reader,
+            &mut io_sampler,
+        );
+        guard.stop();
+        return result;
+    }
+
+    if cli.ndjson {
+        let result = run_ndjson(
+            &shutdown,
+            &mut state,
+            &mut pm_reader,
+            &mut memory_reader,
+            &mut io_sampler,
+        );
+        guard.stop();
+        return result;
+    }
+
+    // A full-screen UI is useless in a pipe or CI log, so fall back to
+    // plain per-sample lines there.
+    if !interactive {
+        let result = run_oneline(
+            &shutdown,
+            &mut state,
+            &mut pm_reader,
+            &mut memory_reader,
+            &mut io_sampler,
+        );
+        guard.stop();
+        state.print_session_summary();
+        return result;
+    }
 
     let result = run_ui(
+        &shutdown,
         &mut state,
         &mut guard,
         &mut timecode,
@@ -120,6 +292,7 @@ fn main() -> Result<()> {
     if let Err(err) = cleanup_terminal() {
         eprintln!("failed to restore terminal: {err}");
     }
+    state.print_session_summary();
 
     if let Err(err) = result {
         eprintln!("asitop exited with error: {err}");
@@ -129,24 +302,200 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-/// Wait for powermetrics to produce the first reading, with timeout
-fn wait_for_reading(reader: &mut PowermetricsReader, wait: Duration) -> Result<PowermetricsReading> {
-    const MAX_ATTEMPTS: u32 = 300; // Wait up to 30 seconds (300 * 100ms)
-    for attempt in 0..MAX_ATTEMPTS {
+/// Restore the terminal before the default hook prints the panic, so the
+/// message and backtrace are readable instead of garbled by raw mode.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        cleanup_terminal().ok();
+        default_hook(info);
+    }));
+}
+
+/// Route SIGTERM, SIGINT and SIGHUP into a flag polled by the UI loop so the
+/// normal shutdown path (reap powermetrics, restore the terminal) always runs.
+fn register_shutdown_signals() -> Result<Arc<AtomicBool>> {
+    let flag = Arc::new(AtomicBool::new(false));
+    for signal in [SIGTERM, SIGINT, SIGHUP] {
+        signal_hook::flag::register(signal, Arc::clone(&flag))
+            .context("failed to install signal handler")?;
+    }
+    Ok(flag)
+}
+
+/// Wait for powermetrics to produce the first reading, with timeout. Polling,
+/// progress messages and the timeout all scale with the sample `interval`,
+/// since the first sample cannot arrive before one interval has passed.
+fn wait_for_reading(
+    shutdown: &AtomicBool,
+    reader: &mut PowermetricsReader,
+    interval: Duration,
+) -> Result<PowermetricsReading> {
+    let poll = (interval / 20).clamp(Duration::from_millis(50), Duration::from_millis(500));
+    let warn_every = (interval * 3).max(Duration::from_secs(5));
+    let timeout = first_reading_timeout(interval);
+    let start = Instant::now();
+    let mut next_warning = warn_every;
+    loop {
+        if shutdown.load(Ordering::Relaxed) {
+            anyhow::bail!("interrupted while waiting for powermetrics");
+        }
         if let Some(reading) = reader.parse()? {
             return Ok(reading);
         }
-        if attempt % 50 == 49 {
-            eprintln!("Still waiting for powermetrics data... ({} seconds)", (attempt + 1) / 10);
+        let waited = start.elapsed();
+        if waited >= timeout {
+            anyhow::bail!(
+                "Timeout waiting for powermetrics data ({}s)",
+                timeout.as_secs()
+            );
+        }
+        if waited >= next_warning {
+            eprintln!(
+                "Still waiting for powermetrics data... ({} seconds)",
+                waited.as_secs()
+            );
+            next_warning += warn_every;
         }
-        thread::sleep(wait);
+        thread::sleep(poll);
     }
-    anyhow::bail!("Timeout waiting for powermetrics data ({}s)", MAX_ATTEMPTS as u64 * wait.as_millis() as u64 / 1000)
+}
+
+/// How long to wait for powermetrics' first sample before giving up.
+fn first_reading_timeout(interval: Duration) -> Duration {
+    (interval * 10).max(Duration::from_secs(30))
+}
+
+/// Collect `--samples` readings without touching the terminal mode and print
+/// a plain-text summary suitable for CI logs.
+fn run_output_once(
+    shutdown: &AtomicBool,
+    state: &mut AppState,
+    pm_reader: &mut PowermetricsReader,
+    memory_reader: &mut MemoryReader,
+    io_sampler: &mut IoSampler,
+) -> Result<()> {
+    let target = state.config.samples.max(1);
+    let mut summary = Summary::new(target as usize);
+    state.record_summary(&mut summary);
+    while summary.samples() < target && !pm_reader.finished() {
+        if shutdown.load(Ordering::Relaxed) {
+            anyhow::bail!("interrupted after {} samples", summary.samples());
+        }
+        if let Some(reading) = pm_reader.parse()?
+            && state.update_if_new(reading, memory_reader, io_sampler)
+        {
+            state.record_summary(&mut summary);
+        }
+        state.report_parse_failure(pm_reader);
+        thread::sleep(Duration::from_millis(100));
+    }
+    print!("{}", summary.render());
+    Ok(())
+}
+
+/// Print the full state as one JSON object per line for every new sample
+/// until interrupted. A closed pipe downstream ends the stream quietly.
+fn run_ndjson(
+    shutdown: &AtomicBool,
+    state: &mut AppState,
+    pm_reader: &mut PowermetricsReader,
+    memory_reader: &mut MemoryReader,
+    io_sampler: &mut IoSampler,
+) -> Result<()> {
+    let mut out = stdout().lock();
+    let mut emit = |row: &serde_json::Value| -> io::Result<()> {
+        serde_json::to_writer(&mut out, row)?;
+        out.write_all(b"\n")?;
+        out.flush()
+    };
+    let result = (|| -> Result<()> {
+        if let Some(row) = state.export_row() {
+            emit(&row)?;
+        }
+        while !shutdown.load(Ordering::Relaxed)
+            && !pm_reader.finished()
+            && !state.duration_elapsed()
+        {
+            if let Some(reading) = pm_reader.parse()?
+                && state.update_if_new(reading, memory_reader, io_sampler)
+                && let Some(row) = state.export_row()
+            {
+                emit(&row)?;
+            }
+            state.report_parse_failure(pm_reader);
+            thread::sleep(Duration::from_millis(100));
+        }
+        Ok(())
+    })();
+    match result {
+        Err(err)
+            if err
+                .downcast_ref::<io::Error>()
+                .is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            Ok(())
+        }
+        other => other,
+    }
+}
+
+/// Rewrite one status line in place on every new sample until interrupted.
+/// When stdout is not a terminal each sample is printed on its own line.
+fn run_oneline(
+    shutdown: &AtomicBool,
+    state: &mut AppState,
+    pm_reader: &mut PowermetricsReader,
+    memory_reader: &mut MemoryReader,
+    io_sampler: &mut IoSampler,
+) -> Result<()> {
+    let mut out = stdout();
+    let tty = out.is_terminal();
+    let print = |out: &mut io::Stdout, line: String| -> Result<()> {
+        if tty {
+            write!(out, "\r{line}\x1b[K")?;
+        } else {
+            writeln!(out, "{line}")?;
+        }
+        out.flush()?;
+        Ok(())
+    };
+    if tty {
+        execute!(out, Hide)?;
+    }
+    let result = (|| -> Result<()> {
+        print(&mut out, state.oneline())?;
+        while !shutdown.load(Ordering::Relaxed)
+            && !pm_reader.finished()
+            && !state.duration_elapsed()
+        {
+            if let Some(reading) = pm_reader.parse()?
+                && state.update_if_new(reading, memory_reader, io_sampler)
+            {
+                print(&mut out, state.oneline())?;
+            }
+            state.report_parse_failure(pm_reader);
+            thread::sleep(Duration::from_millis(100));
+        }
+        Ok(())
+    })();
+    if tty {
+        writeln!(out).ok();
+        execute!(out, Show).ok();
+    }
+    result
 }
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
-    enable_raw_mode()?;
-    execute!(stdout(), EnterAlternateScreen)?;
+    // Restricted terminals may refuse raw mode or the alternate screen; draw
+    // into the normal buffer instead of giving up. Without raw mode keys are
+    // line-buffered, but Ctrl+C still quits through the signal handler.
+    if let Err(err) = enable_raw_mode() {
+        eprintln!("raw mode unavailable ({err}); press Enter after a key");
+    }
+    if execute!(stdout(), EnterAlternateScreen).is_err() {
+        eprintln!("alternate screen unavailable; drawing inline");
+    }
     let backend = CrosstermBackend::new(stdout());
     Terminal::new(backend).map_err(Into::into)
 }
@@ -158,6 +507,7 @@ fn cleanup_terminal() -> Result<()> {
 }
 
 fn run_ui(
+    shutdown: &AtomicBool,
     state: &mut AppState,
     guard: &mut PowermetricsGuard,
     timecode: &mut String,
@@ -166,12 +516,30 @@ fn run_ui(
     io_sampler: &mut IoSampler,
 ) -> Result<()> {
     let mut terminal = setup_terminal()?;
-    let mut last_sample = Instant::now();
-    let poll_rate = Duration::from_millis(100);
+    // Input stays responsive while the data file is only re-read around the
+    // time powermetrics is due to append the next sample.
+    let frame_time = state
+        .config
+        .refresh_rate
+        .map_or(Duration::from_secs(1), |fps| Duration::from_secs(1) / fps);
+    let poll_rate = Duration::from_millis(50).min(frame_time);
+    let parse_retry = Duration::from_millis(100);
+    let mut next_parse = Instant::now();
+    let mut handover: Option<Handover> = None;
     let mut running = true;
     let mut needs_redraw = true;
+    let mut last_draw = Instant::now();
 
     while running {
+        if shutdown.load(Ordering::Relaxed) || pm_reader.finished() || state.duration_elapsed() {
+            break;
+        }
+        if !state.initialized
+            && state.start.elapsed() >= first_reading_timeout(state.sample_duration)
+        {
+            anyhow::bail!("powermetrics never produced a reading");
+        }
+
         if event::poll(poll_rate)? {
             if let Event::Key(key) = event::read()? {
                 match key.code {
@@ -179,34 +547,183 @@ fn run_ui(
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                         running = false;
                     }
+                    KeyCode::Char('d') => {
+                        state.show_diagnostics = !state.show_diagnostics;
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('z') => {
+                        state.cycle_focus();
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('f') => {
+                        state.show_freq_history = !state.show_freq_history;
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('D') => {
+                        let message = match state.append_log_dump() {
+                            Ok(path) => format!("appended diagnostics to {}", path.display()),
+                            Err(err) => format!("log dump failed: {err:#}"),
+                        };
+                        state.status_message = Some((message, Instant::now()));
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        state.scroll_cores(1);
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        state.scroll_cores(-1);
+                        needs_redraw = true;
+                    }
+                    KeyCode::PageDown => {
+                        state.scroll_cores(CORE_SCROLL_PAGE as isize);
+                        needs_redraw = true;
+                    }
+                    KeyCode::PageUp => {
+                        state.scroll_cores(-(CORE_SCROLL_PAGE as isize));
+                        needs_redraw = true;
+                    }
+                    KeyCode::Tab => {
+                        let color = state.cycle_accent();
+                        let message = format!("accent color {color} (--color {color})");
+                        state.status_message = Some((message, Instant::now()));
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('p') => {
+                        let message = match state.save_snapshot(pm_reader.last_chunk()) {
+                            Ok(path) => format!("saved snapshot to {path}"),
+                            Err(err) => format!("snapshot failed: {err:#}"),
+                        };
+                        state.status_message = Some((message, Instant::now()));
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char(' ') => {
+                        state.paused = !state.paused;
+                        state.history_cursor = 0;
+                        needs_redraw = true;
+                    }
+                    KeyCode::Left if state.paused => {
+                        state.move_history_cursor(1);
+                        needs_redraw = true;
+                    }
+                    KeyCode::Right if state.paused => {
+                        state.move_history_cursor(-1);
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('t') => {
+                        state.show_top_processes = !state.show_top_processes;
+                        if state.show_top_processes && !state.tasks_sampler {
+                            // The tasks sampler is only started on demand.
+                            state.tasks_sampler = true;
+                            // A pending replacement still lacks the sampler.
+                            if let Some(pending) = handover.take() {
+                                pending.abandon();
+                            }
+                            restart_powermetrics(state, guard, timecode, pm_reader)?;
+                        }
+                        needs_redraw = true;
+                    }
                     _ => {}
                 }
             }
         }
 
-        if last_sample.elapsed() >= Duration::from_millis(100) {
-            if let Some(reading) = pm_reader.parse()? {
-                if state.update_if_new(reading, memory_reader, io_sampler) {
-                    last_sample = Instant::now();
-                    needs_redraw = true;
+        // While paused the newest sample is picked up on resume instead.
+        if !state.paused && Instant::now() >= next_parse {
+            if let Some(reading) = pm_reader.parse()?
+                && state.update_if_new(reading, memory_reader, io_sampler)
+            {
+                // Wake a little before the next sample is due, then retry
+                // at `parse_retry` until it shows up.
+                next_parse = Instant::now() + state.sample_duration.mul_f32(0.9);
+                needs_redraw = true;
+                if handover.is_none()
+                    && let Some(interval_ms) =
+                        state.auto_interval_step(pm_reader.stats().parse_time)
+                {
+                    handover = Handover::start(state, interval_ms)?;
                 }
+            } else {
+                next_parse = Instant::now() + parse_retry;
+            }
+            state.report_parse_failure(pm_reader);
+            if let Some(err) = pm_reader.remote_error() {
+                state.status_message = Some((format!("reconnecting: {err}"), Instant::now()));
+                needs_redraw = true;
             }
         }
 
-        if state.config.max_count > 0 && state.samples_taken >= state.config.max_count {
-            *timecode = new_timecode();
-            guard.restart(timecode, state.config.interval * 1000)?;
-            pm_reader.set_timecode(timecode);
-            state.samples_taken = 0;
-            state.last_timestamp = None;
+        if state.config.max_count > 0
+            && state.samples_taken >= state.config.max_count
+            && handover.is_none()
+        {
+            handover = Handover::start(state, state.interval_ms)?;
+            if handover.is_none() {
+                state.samples_taken = 0;
+            }
+        }
+        let handed_over = match handover.as_mut() {
+            Some(pending) => pending.reader.parse()?,
+            None => None,
+        };
+        if let Some(pending) = handover.take() {
+            if let Some(reading) = handed_over {
+                // Dropping the old guard stops the old process.
+                let old_timecode = std::mem::replace(timecode, pending.timecode);
+                *guard = pending.guard;
+                *pm_reader = pending.reader;
+                if pending.interval_ms != state.interval_ms {
+                    state.interval_ms = pending.interval_ms;
+                    let message = format!("--auto-interval: now every {}ms", state.interval_ms);
+                    state.status_message = Some((message, Instant::now()));
+                }
+                fs::remove_file(powermetrics_path(&old_timecode)).ok();
+                state.samples_taken = 0;
+                needs_redraw |= state.update_if_new(reading, memory_reader, io_sampler);
+            } else if pending.started.elapsed() >= first_reading_timeout(state.sample_duration) {
+                // Keep the working process and try again after another
+                // `--max-count` samples.
+                pending.abandon();
+                state.samples_taken = 0;
+                state.status_message = Some((
+                    "replacement powermetrics produced no reading; keeping the current one"
+                        .to_string(),
+                    Instant::now(),
+                ));
+                needs_redraw = true;
+            } else {
+                handover = Some(pending);
+            }
+        }
+
+        // Keep the session clock ticking (and interpolated values moving)
+        // between samples.
+        if last_draw.elapsed() >= frame_time {
+            needs_redraw = true;
         }
 
         if needs_redraw {
+            let mut core_scroll_limit = None;
             terminal.draw(|f| {
-                let snapshot = state.snapshot();
+                let mut snapshot = state.snapshot();
+                if state.show_diagnostics {
+                    snapshot.diagnostics = Some(Diagnostics {
+                        reader: pm_reader.stats(),
+                        dropped_samples: state.dropped_samples,
+                        timecode: timecode.clone(),
+                        gpu_sources: state.gpu_source_mismatch(),
+                        ane_source: state.ane_source(),
+                        parse_failure: pm_reader.failure().map(|failure| failure.error.clone()),
+                    });
+                }
                 ui::draw(f, &snapshot);
+                core_scroll_limit = snapshot.core_scroll_limit.get();
             })?;
+            if let Some(limit) = core_scroll_limit {
+                state.clamp_core_scroll(limit);
+            }
             needs_redraw = false;
+            last_draw = Instant::now();
         }
     }
 
@@ -214,6 +731,29 @@ fn run_ui(
     Ok(())
 }
 
+/// Restart powermetrics into a fresh timecoded file with the current sampler set.
+fn restart_powermetrics(
+    state: &mut AppState,
+    guard: &mut PowermetricsGuard,
+    timecode: &mut String,
+    pm_reader: &mut PowermetricsReader,
+) -> Result<()> {
+    if state.config.from_file.is_some() || state.config.connect.is_some() {
+        // Replays and remote streams have no process to restart.
+        state.samples_taken = 0;
+        return Ok(());
+    }
+    *timecode = new_timecode();
+    guard.restart(timecode, state.interval_ms, state.tasks_sampler)?;
+    pm_reader.set_timecode(timecode);
+    state.samples_taken = 0;
+    state.last_timestamp = None;
+    Ok(())
+}
+
+/// Number of entries in the `--color` palette.
+const ACCENT_COLORS: u8 = COLOR_NAMES.len() as u8;
+
 fn color_from_arg(arg: u8) -> Color {
     match arg {
         0 => Color::Reset,
@@ -224,47 +764,324 @@ fn color_from_arg(arg: u8) -> Color {
         5 => Color::Magenta,
         6 => Color::Cyan,
         7 => Color::White,
-        8 => Color::LightMagenta,
-        _ => Color::Green,
+        // `--color` is range-checked when parsed.
+        _ => Color::LightMagenta,
+    }
+}
+
+/// Broken-down local time for `time`.
+fn local_tm(time: SystemTime) -> Option<libc::tm> {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as libc::time_t)
+        .unwrap_or(0);
+    // SAFETY: localtime_r only writes into the zeroed `tm` we own.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&secs, &mut tm) }.is_null() {
+        return None;
+    }
+    Some(tm)
+}
+
+/// Format a timestamp as local wall-clock `HH:MM:SS`.
+fn local_clock(time: SystemTime) -> String {
+    match local_tm(time) {
+        Some(tm) => format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec),
+        None => "--:--:--".into(),
+    }
+}
+
+/// Format a timestamp as `YYYYMMDD-HHMMSS` for file names.
+fn local_file_stamp(time: SystemTime) -> String {
+    let Some(tm) = local_tm(time) else {
+        return "unknown".into();
+    };
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// Rough efficiency figure: watts per GHz of work actually done, i.e. per
+/// sum of core frequency weighted by the fraction of time each core was busy.
+fn watts_per_active_ghz(package_power: f32, cpu: &CpuMetrics) -> Option<f32> {
+    let active_ghz: f32 = cpu
+        .e_cores
+        .iter()
+        .chain(&cpu.p_cores)
+        .filter(|core| !core.down)
+        .map(|core| core.freq_mhz as f32 / 1000.0 * core.active_pct.min(100) as f32 / 100.0)
+        .sum();
+    (active_ghz >= 0.01).then(|| package_power / active_ghz)
+}
+
+/// Field-wise mean of reports with the same shape: numbers are averaged,
+/// objects and arrays recurse, and anything else (names, clock strings,
+/// booleans) keeps the latest value.
+fn average_json(values: &[serde_json::Value]) -> serde_json::Value {
+    use serde_json::Value;
+    let Some(last) = values.last() else {
+        return Value::Null;
+    };
+    match last {
+        Value::Number(_) => {
+            let numbers: Vec<f64> = values.iter().filter_map(Value::as_f64).collect();
+            if numbers.len() != values.len() {
+                return last.clone();
+            }
+            let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+            // Integer fields (percentages, MHz, ids) stay integers.
+            if values.iter().all(Value::is_u64) {
+                return Value::from(mean.round() as u64);
+            }
+            serde_json::Number::from_f64(mean).map_or_else(|| last.clone(), Value::Number)
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .keys()
+                .map(|key| {
+                    let column: Vec<Value> = values
+                        .iter()
+                        .filter_map(|value| value.get(key).cloned())
+                        .collect();
+                    (key.clone(), average_json(&column))
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            (0..items.len())
+                .map(|index| {
+                    let column: Vec<Value> = values
+                        .iter()
+                        .filter_map(|value| value.get(index).cloned())
+                        .collect();
+                    average_json(&column)
+                })
+                .collect(),
+        ),
+        _ => last.clone(),
+    }
+}
+
+/// Relative gap between `combined_power` and the component sum that is
+/// worth surfacing.
+const COMBINED_POWER_TOLERANCE: f32 = 0.05;
+
+/// Core grid rows moved by PageUp/PageDown.
+const CORE_SCROLL_PAGE: usize = 4;
+
+/// Samples averaged, and waited for after each change, before
+/// `--auto-interval` adjusts the interval again.
+const AUTO_INTERVAL_SAMPLES: usize = 5;
+/// Parsing share of a sample above which `--auto-interval` doubles the
+/// interval, and below which it halves it.
+const AUTO_INTERVAL_BUSY: f32 = 0.05;
+const AUTO_INTERVAL_IDLE: f32 = 0.005;
+
+/// Size at which the 'D' dump log is rotated to `<name>.1`.
+const LOG_MAX_BYTES: u64 = 1024 * 1024;
+
+/// How long a status message such as "saved snapshot" stays on screen.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Post a macOS notification through `osascript` without blocking the UI.
+fn post_notification(title: &str, message: &str) {
+    let script = format!("display notification {:?} with title {:?}", message, title);
+    thread::spawn(move || {
+        let _ = Command::new("osascript")
+            .args(["-e", &script])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    });
+}
+
+/// Number of processes listed in the top energy panel.
+const TOP_PROCESS_COUNT: usize = 5;
+
+/// `--smooth` rolling averages for the cluster and GPU usage gauges.
+struct UsageSmoothing {
+    e: RollingAverage,
+    p: RollingAverage,
+    gpu: RollingAverage,
+}
+
+/// Headline power values of one sample.
+#[derive(Clone, Copy, Default)]
+struct PowerFrame {
+    cpu: f32,
+    gpu: f32,
+    ane: f32,
+    package: f32,
+}
+
+impl PowerFrame {
+    fn lerp(self, to: PowerFrame, t: f32) -> PowerFrame {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        PowerFrame {
+            cpu: mix(self.cpu, to.cpu),
+            gpu: mix(self.gpu, to.gpu),
+            ane: mix(self.ane, to.ane),
+            package: mix(self.package, to.package),
+        }
     }
 }
 
 struct AppState {
     config: Cli,
     soc: SocInfo,
-    color: Color,
+    theme: Theme,
     memory_stats: MemoryStats,
     cpu_metrics: CpuMetrics,
     gpu_metrics: GpuMetrics,
     io_stats: IoStats,
     thermal_pressure: String,
     thermal_level: Option<ThermalLevel>,
+    /// `None` on machines without a battery.
+    battery: Option<BatteryStats>,
+    /// `None` when the GPU driver publishes no statistics.
+    accelerator: Option<AcceleratorStats>,
+    /// GPU usage as reported by powermetrics, before `--gpu-source` and
+    /// smoothing are applied.
+    powermetrics_gpu_pct: u64,
+    /// Throttle state of the previous sample, for edge-triggered alerts.
+    throttled: bool,
     last_timestamp: Option<std::time::SystemTime>,
+    /// Duration of the latest powermetrics sample; the authoritative time base
+    /// for every per-second value derived from a reading.
+    sample_duration: Duration,
+    /// Interval powermetrics was asked for; `--auto-interval` changes it.
+    interval_ms: u64,
+    /// Share of each sample's duration spent parsing it, for `--auto-interval`.
+    parse_overhead: RollingAverage,
     power_history: History,
+    /// Thermal severity per sample, aligned with `power_history`.
+    thermal_history: History<u8>,
+    /// Cluster and GPU clocks in MHz per sample, aligned with `power_history`.
+    e_freq_history: History<u64>,
+    p_freq_history: History<u64>,
+    gpu_freq_history: History<u64>,
+    /// `f` shows the clock histories in the processor panel.
+    show_freq_history: bool,
     cpu_avg: RollingAverage,
     gpu_avg: RollingAverage,
     package_avg: RollingAverage,
     cpu_peak: f32,
     gpu_peak: f32,
     package_peak: f32,
+    /// Package energy integrated over every sample since launch.
+    energy_wh: f64,
     cpu_power: f32,
     gpu_power: f32,
+    /// Sum of CPU, GPU, ANE and (when reported) DRAM power.
     package_power: f32,
+    /// powermetrics' `combined_power`, for comparison with `package_power`.
+    combined_power: f32,
+    dram_power: Option<f32>,
+    /// Power values of the sample before the current one, and when the
+    /// current one arrived, for `--refresh-rate` interpolation.
+    previous_power: PowerFrame,
+    sample_received: Instant,
+    /// Package power per active GHz across all cores; `None` when idle.
+    watts_per_ghz: Option<f32>,
     ane_percent: u64,
     ane_power: f32,
+    watched_task: Option<TaskMetrics>,
+    watched_seen: bool,
+    top_tasks: Vec<TaskMetrics>,
+    /// System-wide energy impact while the `tasks` sampler runs.
+    energy_impact: Option<f32>,
+    /// Whole-machine power from powermetrics, when it reports it.
+    reported_system_power: Option<f32>,
+    show_top_processes: bool,
+    /// Whether the running powermetrics includes the `tasks` sampler.
+    tasks_sampler: bool,
     pub samples_taken: u64,
+    /// Readings discarded because their timestamp was not newer than the last.
+    dropped_samples: u64,
+    show_diagnostics: bool,
+    /// Package power is currently above `--alert-watts`.
+    power_alert: bool,
+    /// `None` unless `--smooth` is above 1.
+    smoothing: Option<UsageSmoothing>,
+    /// Deduplicated `--panels`.
+    panels: Vec<Panel>,
+    /// Panel maximized with `z`; `None` shows the grid.
+    focus: Option<Panel>,
+    /// Rows scrolled past in the per-core panels.
+    core_scroll: usize,
+    /// Furthest `core_scroll` the core panels allowed at the last draw.
+    core_scroll_max: usize,
+    /// Whether any powermetrics reading has been applied yet.
+    initialized: bool,
+    /// Whether a core count divergence from `SocInfo` was already reported.
+    core_count_warned: bool,
+    /// Power summary of the whole run, printed on exit with `--summary`.
+    session_summary: Option<Summary>,
+    /// Space freezes the display so the power history can be inspected.
+    paused: bool,
+    /// Samples back from the newest that the paused history cursor sits on.
+    history_cursor: usize,
+    /// `--web` dashboard, fed a fresh report after every sample.
+    web: Option<WebServer>,
+    /// The latest reading, kept only while `--web` is on so it can be
+    /// served to `--connect` clients.
+    last_reading: Option<PowermetricsReading>,
+    /// `--record` output, dropped after the first write error.
+    recorder: Option<PowerRecorder>,
+    /// Reports collected towards the next `--export-average` row.
+    export_window: Vec<serde_json::Value>,
+    /// Transient message shown in the power panel, with when it was set.
+    status_message: Option<(String, Instant)>,
+    start: Instant,
 }
 
 impl AppState {
     fn new(cli: Cli, soc: SocInfo, memory_reader: &mut MemoryReader) -> Self {
-        let interval_seconds = std::cmp::max(cli.interval, 1);
-        let avg_window = std::cmp::max(1, (cli.avg / interval_seconds) as usize);
+        let interval_ms = cli.sample_interval_ms();
+        let avg_window = rolling_window(cli.avg, interval_ms);
+        let history_len =
+            std::cmp::max(1, (cli.history.saturating_mul(1000) / interval_ms) as usize);
+        let show_top_processes = cli.top_processes;
+        let show_diagnostics = cli.verbose;
+        let panels = cli.panel_order();
+        let smooth_samples = cli.smooth;
+        // Size the p95 window for the shortest interval the run can reach, so
+        // it still spans the whole `--duration` after `--auto-interval`
+        // halves the interval.
+        let shortest_interval_ms = if cli.auto_interval {
+            cli.auto_interval_range().0
+        } else {
+            interval_ms
+        }
+        .max(1);
+        let session_summary = cli.summary.then(|| {
+            let samples = cli.duration.unwrap_or(0).saturating_mul(1000) / shortest_interval_ms;
+            Summary::new(usize::try_from(samples).unwrap_or(usize::MAX))
+        });
+        let mut theme = Theme::preset(cli.theme);
+        if let Some(color) = cli.color {
+            theme.accent = color_from_arg(color);
+        }
+        if let Some(file) = &cli.theme_file {
+            theme = theme.with_file(file);
+        }
+        if cli.monochrome() {
+            theme = Theme::monochrome();
+        }
+        theme.load_thresholds = cli.load_thresholds();
+        let tasks_sampler = cli.needs_tasks();
         let mut memory_stats = memory_reader.read();
         if (memory_stats.total_gb - memory_stats.used_gb).abs() < f32::EPSILON {
             memory_stats.used_gb = memory_stats.total_gb;
         }
         Self {
-            color: color_from_arg(cli.color),
+            theme,
             config: cli,
             soc,
             memory_stats,
@@ -273,31 +1090,106 @@ impl AppState {
             io_stats: IoStats::default(),
             thermal_pressure: String::new(),
             thermal_level: None,
+            throttled: false,
+            battery: None,
+            accelerator: None,
+            powermetrics_gpu_pct: 0,
             last_timestamp: None,
-            power_history: History::new(120),
+            sample_duration: Duration::from_millis(interval_ms),
+            interval_ms,
+            parse_overhead: RollingAverage::new(AUTO_INTERVAL_SAMPLES),
+            power_history: History::new(history_len),
+            thermal_history: History::new(history_len),
+            e_freq_history: History::new(history_len),
+            p_freq_history: History::new(history_len),
+            gpu_freq_history: History::new(history_len),
+            show_freq_history: false,
             cpu_avg: RollingAverage::new(avg_window),
             gpu_avg: RollingAverage::new(avg_window),
             package_avg: RollingAverage::new(avg_window),
             cpu_peak: 0.0,
             gpu_peak: 0.0,
             package_peak: 0.0,
+            energy_wh: 0.0,
             cpu_power: 0.0,
             gpu_power: 0.0,
             package_power: 0.0,
+            combined_power: 0.0,
+            dram_power: None,
+            watts_per_ghz: None,
+            previous_power: PowerFrame::default(),
+            sample_received: Instant::now(),
             ane_percent: 0,
             ane_power: 0.0,
+            watched_task: None,
+            watched_seen: false,
+            top_tasks: Vec::new(),
+            energy_impact: None,
+            reported_system_power: None,
+            show_top_processes,
+            tasks_sampler,
             samples_taken: 0,
+            dropped_samples: 0,
+            show_diagnostics,
+            power_alert: false,
+            smoothing: (smooth_samples > 1).then(|| UsageSmoothing {
+                e: RollingAverage::new(smooth_samples),
+                p: RollingAverage::new(smooth_samples),
+                gpu: RollingAverage::new(smooth_samples),
+            }),
+            panels,
+            focus: None,
+            core_scroll: 0,
+            core_scroll_max: 0,
+            status_message: None,
+            initialized: false,
+            core_count_warned: false,
+            session_summary,
+            export_window: Vec::new(),
+            recorder: None,
+            web: None,
+            last_reading: None,
+            paused: false,
+            history_cursor: 0,
+            start: Instant::now(),
         }
     }
 
-    fn apply_reading(&mut self, reading: PowermetricsReading, io_sampler: &mut IoSampler) {
+    fn apply_reading(&mut self, mut reading: PowermetricsReading, io_sampler: &mut IoSampler) {
+        // A `--connect` sample carries the remote machine's own stats, which
+        // replace anything read locally.
+        let host = reading.host.take();
+        if self.web.is_some() {
+            self.last_reading = Some(reading.clone());
+        }
+        self.update_sample_duration(&reading);
         self.last_timestamp = Some(reading.timestamp);
+        self.update_tasks(reading.tasks);
+        self.energy_impact = reading.energy_impact;
+        self.reported_system_power = reading.system_power_w;
         self.thermal_pressure = reading.thermal_pressure;
         self.cpu_metrics = reading.cpu;
         self.gpu_metrics = reading.gpu;
-        self.refresh_thermal_level();
+        if let Some(host) = &host {
+            self.soc = host.soc.clone();
+            self.memory_stats = host.memory.clone();
+        }
+        self.reconcile_core_counts();
+        self.apply_gpu_source(host.as_ref());
+        self.smooth_usage();
+        self.refresh_thermal_level(host.as_ref());
+        self.battery = match &host {
+            Some(host) => host.battery,
+            None => read_battery(),
+        };
         self.update_power_stats();
-        self.refresh_io(io_sampler);
+        match &host {
+            Some(host) => self.io_stats = host.io,
+            None => self.refresh_io(io_sampler),
+        }
+        self.record_session();
+        self.publish_web();
+        self.initialized = true;
         self.samples_taken += 1;
     }
 
@@ -309,33 +1201,182 @@ impl AppState {
     ) -> bool {
         if let Some(last) = self.last_timestamp {
             if reading.timestamp <= last {
+                self.dropped_samples += 1;
                 return false;
             }
         }
-        self.last_timestamp = Some(reading.timestamp);
-        self.thermal_pressure = reading.thermal_pressure;
-        self.cpu_metrics = reading.cpu;
-        self.gpu_metrics = reading.gpu;
         self.memory_stats = memory_reader.read();
-        self.refresh_thermal_level();
-        self.update_power_stats();
-        self.refresh_io(io_sampler);
-        self.samples_taken += 1;
+        self.apply_reading(reading, io_sampler);
         true
     }
 
+    /// sysctl's perflevel ordering and logical CPU counts do not always match
+    /// the E/P split powermetrics reports, so prefer the cores actually seen.
+    /// The first divergence is reported once in the status line.
+    fn reconcile_core_counts(&mut self) {
+        let e_seen = self.cpu_metrics.e_cores.len() as u32;
+        let p_seen = self.cpu_metrics.p_cores.len() as u32;
+        // Clusters without a per-core list give no evidence either way.
+        if e_seen > 0 && p_seen > 0 {
+            let (e_expected, p_expected) = (self.soc.e_core_count, self.soc.p_core_count);
+            if (e_seen, p_seen) != (e_expected, p_expected) && !self.core_count_warned {
+                self.core_count_warned = true;
+                self.status_message = Some((
+                    format!(
+                        "powermetrics lists {e_seen}E+{p_seen}P cores, sysctl reported {e_expected}E+{p_expected}P; using powermetrics"
+                    ),
+                    Instant::now(),
+                ));
+            }
+            self.soc.e_core_count = e_seen;
+            self.soc.p_core_count = p_seen;
+        }
+    }
+
+    /// Refresh the IOKit accelerator statistics (the remote's, under
+    /// `--connect`) and, with `--gpu-source iokit`, take GPU usage from the
+    /// driver instead of powermetrics.
+    fn apply_gpu_source(&mut self, host: Option<&HostStats>) {
+        self.accelerator = match host {
+            Some(host) => host.accelerator,
+            None => read_accelerator(),
+        };
+        self.powermetrics_gpu_pct = self.gpu_metrics.active_pct;
+        if self.config.gpu_source == GpuSource::Iokit
+            && let Some(pct) = self.iokit_gpu_pct()
+        {
+            self.gpu_metrics.active_pct = pct;
+            self.gpu_metrics.available = true;
+        }
+    }
+
+    fn iokit_gpu_pct(&self) -> Option<u64> {
+        self.accelerator
+            .and_then(|stats| stats.device_utilization_pct)
+    }
+
+    /// Under `--auto-interval`, record how long the newest sample took to
+    /// parse and return the interval to switch to, if any: double while
+    /// parsing is a noticeable share of each sample, halve while it is
+    /// negligible. Waits for `AUTO_INTERVAL_SAMPLES` since the last switch.
+    fn auto_interval_step(&mut self, parse_time: Duration) -> Option<u64> {
+        if !self.config.auto_interval {
+            return None;
+        }
+        self.parse_overhead
+            .push(parse_time.as_secs_f32() / self.sample_seconds());
+        if self.samples_taken < AUTO_INTERVAL_SAMPLES as u64 {
+            return None;
+        }
+        let overhead = self.parse_overhead.average();
+        let (min, max) = self.config.auto_interval_range();
+        let next = if overhead > AUTO_INTERVAL_BUSY {
+            self.interval_ms.saturating_mul(2).min(max)
+        } else if overhead < AUTO_INTERVAL_IDLE {
+            (self.interval_ms / 2).max(min)
+        } else {
+            self.interval_ms
+        };
+        (next != self.interval_ms).then_some(next)
+    }
+
+    /// Where `ane_percent` comes from, for the diagnostics overlay.
+    fn ane_source(&self) -> &'static str {
+        let strings = Strings::for_lang(self.config.lang);
+        if self.cpu_metrics.ane_active_pct.is_some() {
+            strings.ane_from_residency
+        } else {
+            strings.ane_from_estimate
+        }
+    }
+
+    /// Both GPU usage readings, `(powermetrics, iokit)`, when they disagree.
+    fn gpu_source_mismatch(&self) -> Option<(u64, u64)> {
+        let iokit = self.iokit_gpu_pct()?;
+        (iokit != self.powermetrics_gpu_pct).then_some((self.powermetrics_gpu_pct, iokit))
+    }
+
+    /// Replace the cluster and GPU usage with their `--smooth` averages.
+    fn smooth_usage(&mut self) {
+        let Some(smoothing) = self.smoothing.as_mut() else {
+            return;
+        };
+        let smooth = |average: &mut RollingAverage, value: &mut u64| {
+            average.push(*value as f32);
+            *value = average.average().round() as u64;
+        };
+        smooth(&mut smoothing.e, &mut self.cpu_metrics.e_cluster_active);
+        smooth(&mut smoothing.p, &mut self.cpu_metrics.p_cluster_active);
+        smooth(&mut smoothing.gpu, &mut self.gpu_metrics.active_pct);
+    }
+
+    /// Prefer the plist's `elapsed_ns`, then the gap between sample timestamps,
+    /// and only fall back to the configured interval when neither is known.
+    fn update_sample_duration(&mut self, reading: &PowermetricsReading) {
+        let from_timestamps = self
+            .last_timestamp
+            .and_then(|last| reading.timestamp.duration_since(last).ok())
+            .filter(|gap| !gap.is_zero());
+        self.sample_duration = reading
+            .elapsed
+            .or(from_timestamps)
+            .unwrap_or_else(|| Duration::from_millis(self.interval_ms));
+    }
+
+    fn sample_seconds(&self) -> f32 {
+        self.sample_duration.as_secs_f32().max(0.001)
+    }
+
+    fn update_tasks(&mut self, mut tasks: Vec<TaskMetrics>) {
+        self.update_watched_process(&tasks);
+        tasks.sort_by(|a, b| b.energy_impact.total_cmp(&a.energy_impact));
+        tasks.truncate(TOP_PROCESS_COUNT);
+        self.top_tasks = tasks;
+    }
+
+    fn update_watched_process(&mut self, tasks: &[TaskMetrics]) {
+        let Some(target) = self.config.watch_process.as_deref() else {
+            return;
+        };
+        let pid = target.parse::<i64>().ok();
+        self.watched_task = tasks
+            .iter()
+            .find(|task| match pid {
+                Some(pid) => task.pid == pid,
+                None => task.name.eq_ignore_ascii_case(target),
+            })
+            .cloned();
+        if self.watched_task.is_some() {
+            self.watched_seen = true;
+        }
+    }
+
     fn refresh_io(&mut self, sampler: &mut IoSampler) {
         self.io_stats = sampler.sample();
     }
 
     fn update_power_stats(&mut self) {
-        let interval = std::cmp::max(self.config.interval, 1) as f32;
+        // powermetrics reports energy accumulated over its own sample window, so
+        // normalise by the window it actually measured rather than `--interval`.
+        // `combined_power` is already an average power and needs no scaling.
+        let interval = self.sample_seconds();
+        self.previous_power = self.power_frame();
+        self.sample_received = Instant::now();
         self.cpu_power = self.cpu_metrics.cpu_w / interval;
         self.gpu_power = self.cpu_metrics.gpu_w / interval;
-        self.package_power = self.cpu_metrics.package_w / interval;
         self.ane_power = self.cpu_metrics.ane_w / interval;
-        let ane_max = self.soc.ane_max_power.max(1.0);
-        self.ane_percent = ((self.ane_power / ane_max) * 100.0).clamp(0.0, 100.0).round() as u64;
+        self.dram_power = self.cpu_metrics.dram_w.map(|w| w / interval);
+        self.combined_power = self.cpu_metrics.package_w;
+        self.package_power =
+            self.cpu_power + self.gpu_power + self.ane_power + self.dram_power.unwrap_or(0.0);
+        self.energy_wh += self.package_power as f64 * interval as f64 / 3600.0;
+        self.watts_per_ghz = watts_per_active_ghz(self.package_power, &self.cpu_metrics);
+        self.ane_percent = self.cpu_metrics.ane_active_pct.unwrap_or_else(|| {
+            let ane_max = self.soc.ane_max_power.max(1.0);
+            ((self.ane_power / ane_max) * 100.0)
+                .clamp(0.0, 100.0)
+                .round() as u64
+        });
 
         self.cpu_peak = self.cpu_peak.max(self.cpu_power);
         self.gpu_peak = self.gpu_peak.max(self.gpu_power);
@@ -343,59 +1384,644 @@ impl AppState {
         self.cpu_avg.push(self.cpu_power);
         self.gpu_avg.push(self.gpu_power);
         self.package_avg.push(self.package_power);
-        self.power_history.push(self.cpu_power + self.gpu_power);
+        let at = self.last_timestamp.unwrap_or_else(SystemTime::now);
+        self.power_history.push(at, self.cpu_power + self.gpu_power);
+        self.push_thermal_history(at);
+        self.e_freq_history
+            .push(at, self.cpu_metrics.e_cluster_freq_mhz);
+        self.p_freq_history
+            .push(at, self.cpu_metrics.p_cluster_freq_mhz);
+        self.gpu_freq_history.push(at, self.gpu_metrics.freq_mhz);
+        self.record_power(at);
+        self.update_power_alert();
     }
 
-    fn snapshot(&self) -> UiSnapshot<'_> {
-        let thermal_throttle = self
+    /// Move the paused history cursor `delta` samples back in time,
+    /// clamped to the stored history.
+    fn move_history_cursor(&mut self, delta: isize) {
+        let oldest = self.power_history.len().saturating_sub(1);
+        self.history_cursor = self.history_cursor.saturating_add_signed(delta).min(oldest);
+    }
+
+    fn record_power(&mut self, at: SystemTime) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        if let Err(err) = recorder.write(at, self.cpu_power, self.gpu_power, self.package_power) {
+            self.recorder = None;
+            self.status_message = Some((format!("--record stopped: {err:#}"), Instant::now()));
+        }
+    }
+
+    /// Clock histories for the processor panel: toggled with `f`, and always
+    /// shown while the panel is maximized.
+    fn freq_history(&self) -> Option<FreqHistory> {
+        if !self.show_freq_history && self.focus != Some(Panel::Processor) {
+            return None;
+        }
+        Some(FreqHistory {
+            e_cluster: self.e_freq_history.timeline(self.history_interval()),
+            p_cluster: self.p_freq_history.timeline(self.history_interval()),
+            gpu: self.gpu_freq_history.timeline(self.history_interval()),
+        })
+    }
+
+    /// Expected spacing of history samples, for spotting gaps.
+    fn history_interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    fn push_thermal_history(&mut self, at: SystemTime) {
+        let severity = match self.thermal_level {
+            Some(level) => level.severity(),
+            None if self.throttled => ThermalLevel::Danger.severity(),
+            None => ThermalLevel::Normal.severity(),
+        };
+        self.thermal_history.push(at, severity);
+    }
+
+    fn cpu_power_snapshot(&self) -> PowerSnapshot {
+        PowerSnapshot::new(
+            self.cpu_power,
+            self.cpu_avg.average(),
+            self.cpu_peak,
+            self.cpu_avg.percentile(95.0),
+            self.soc.cpu_max_power,
+        )
+    }
+
+    fn gpu_power_snapshot(&self) -> PowerSnapshot {
+        PowerSnapshot::new(
+            self.gpu_power,
+            self.gpu_avg.average(),
+            self.gpu_peak,
+            self.gpu_avg.percentile(95.0),
+            self.soc.gpu_max_power,
+        )
+    }
+
+    /// The package has no published TDP, so its percentage stays at zero.
+    fn package_power_snapshot(&self) -> PowerSnapshot {
+        PowerSnapshot::new(
+            self.package_power,
+            self.package_avg.average(),
+            self.package_peak,
+            self.package_avg.percentile(95.0),
+            0.0,
+        )
+    }
+
+    /// powermetrics' `combined_power` when it disagrees noticeably with the
+    /// component sum shown as the headline.
+    fn combined_power_divergence(&self) -> Option<f32> {
+        let tolerance = (self.package_power * COMBINED_POWER_TOLERANCE).max(0.1);
+        (self.combined_power > 0.0 && (self.combined_power - self.package_power).abs() > tolerance)
+            .then_some(self.combined_power)
+    }
+
+    /// Track threshold crossings so a sustained spike notifies only once.
+    fn update_power_alert(&mut self) {
+        let Some(limit) = self.config.alert_watts else {
+            return;
+        };
+        let over = self.package_power > limit;
+        if over && !self.power_alert && self.config.alert_notify {
+            post_notification(
+                "asitop",
+                &format!(
+                    "Package power {:.1}W exceeded {:.1}W",
+                    self.package_power, limit
+                ),
+            );
+        }
+        self.power_alert = over;
+    }
+
+    /// Step the accent color to the next `--color` palette entry and return
+    /// its index. Without `--color` the cycle starts after the theme's own
+    /// accent. The choice is not written anywhere: asitop has no config file
+    /// and `--theme-file` is the user's own JSON, so the status line names
+    /// the `--color` flag to pass next time instead.
+    fn cycle_accent(&mut self) -> u8 {
+        let current = self.config.color.or_else(|| {
+            (0..ACCENT_COLORS).find(|&color| color_from_arg(color) == self.theme.accent)
+        });
+        let next = current.map_or(0, |color| color.saturating_add(1) % ACCENT_COLORS);
+        self.config.color = Some(next);
+        if !self.config.monochrome() {
+            self.theme.accent = color_from_arg(next);
+        }
+        next
+    }
+
+    /// Status line printed by `--oneline`.
+    fn oneline(&self) -> String {
+        let thermal = self
             .thermal_level
-            .map(|level| level.is_throttled())
-            .unwrap_or_else(|| self.thermal_pressure.trim() != "Nominal");
+            .map(|level| level.label())
+            .unwrap_or_else(|| self.thermal_pressure.trim());
+        format!(
+            "CPU {:.1}W | GPU {:.1}W | {}%RAM | {}",
+            self.cpu_power, self.gpu_power, self.memory_stats.used_percent, thermal
+        )
+    }
+
+    /// Scroll the core grid by `rows`, up to the last full page of rows the
+    /// renderer reported at the previous draw.
+    fn scroll_cores(&mut self, rows: isize) {
+        self.core_scroll = self
+            .core_scroll
+            .saturating_add_signed(rows)
+            .min(self.core_scroll_max);
+    }
+
+    /// Take the scroll limit the renderer worked out for the current
+    /// terminal size, so presses past the last row are not banked.
+    fn clamp_core_scroll(&mut self, limit: usize) {
+        self.core_scroll_max = limit;
+        self.core_scroll = self.core_scroll.min(limit);
+    }
+
+    /// Maximize the next panel in `--panels` order, returning to the grid
+    /// after the last one.
+    fn cycle_focus(&mut self) {
+        let next = match self.focus {
+            None => 0,
+            Some(focused) => self
+                .panels
+                .iter()
+                .position(|panel| *panel == focused)
+                .map_or(0, |index| index + 1),
+        };
+        self.focus = self.panels.get(next).copied();
+    }
+
+    /// Write the current state as JSON next to the raw plist it was parsed
+    /// from, for attaching to bug reports. Returns the JSON file name.
+    fn save_snapshot(&self, raw_plist: &[u8]) -> Result<String> {
+        let base = format!("asitop-snapshot-{}", local_file_stamp(SystemTime::now()));
+        let json_path = format!("{base}.json");
+        fs::write(&json_path, serde_json::to_string_pretty(&self.report())?)
+            .with_context(|| format!("failed to write {json_path}"))?;
+        let plist_path = format!("{base}.plist");
+        fs::write(&plist_path, raw_plist)
+            .with_context(|| format!("failed to write {plist_path}"))?;
+        Ok(json_path)
+    }
+
+    /// Append a readable dump of the config, terminal size and current
+    /// metrics to the `--log-file`, rotating it once it passes
+    /// `LOG_MAX_BYTES`. Returns the log path.
+    fn append_log_dump(&self) -> Result<PathBuf> {
+        let terminal = terminal::size()
+            .map(|(cols, rows)| format!("{cols}x{rows}"))
+            .unwrap_or_else(|_| "unknown".into());
+        let mut dump = format!(
+            "==== asitop dump {} ====\nterminal: {terminal}\nuptime: {}s\nconfig: {:#?}\n",
+            local_file_stamp(SystemTime::now()),
+            self.start.elapsed().as_secs(),
+            self.config
+        );
+        dump.push_str(&serde_json::to_string_pretty(&self.report())?);
+        dump.push_str("\n\n");
+        self.append_log(&dump)
+    }
+
+    /// Append `entry` to `--log-file` (default `~/Library/Logs/asitop.log`),
+    /// rotating it first once it has grown past `LOG_MAX_BYTES`.
+    fn append_log(&self, entry: &str) -> Result<PathBuf> {
+        let path = self.config.log_file.clone().unwrap_or_else(|| {
+            std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join("Library/Logs/asitop.log"))
+                .unwrap_or_else(|| PathBuf::from("asitop.log"))
+        });
+        if fs::metadata(&path).is_ok_and(|meta| meta.len() >= LOG_MAX_BYTES) {
+            let mut rotated = path.clone().into_os_string();
+            rotated.push(".1");
+            fs::rename(&path, &rotated)
+                .with_context(|| format!("failed to rotate {}", path.display()))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        file.write_all(entry.as_bytes())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Under `--verbose`, log why complete powermetrics samples stopped
+    /// parsing (the error and the start of the sample), so format changes in
+    /// new macOS releases can be diagnosed instead of showing stale data.
+    fn report_parse_failure(&mut self, pm_reader: &mut PowermetricsReader) {
+        if !self.config.verbose {
+            return;
+        }
+        let Some(failure) = pm_reader.take_failure() else {
+            return;
+        };
+        let entry = format!(
+            "==== asitop parse failure {} ====\nerror: {}\nsample starts with:\n{}\n\n",
+            local_file_stamp(SystemTime::now()),
+            failure.error,
+            failure.preview
+        );
+        let message = match self.append_log(&entry) {
+            Ok(path) => format!("a sample failed to parse; logged to {}", path.display()),
+            Err(_) => format!("a sample failed to parse: {}", failure.error),
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Current SoC info, metrics and power statistics, shared by the JSON
+    /// snapshot and the 'D' log dump.
+    fn report(&self) -> serde_json::Value {
+        serde_json::json!({
+            "soc": self.soc,
+            "sample_time": self.last_timestamp.map(local_clock),
+            "sample_unix": self
+                .last_timestamp
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_secs_f64()),
+            "sample_seconds": self.sample_seconds(),
+            "thermal_pressure": self.thermal_pressure,
+            "thermal_level": self.thermal_level.map(|level| format!("{level:?}")),
+            "cpu": self.cpu_metrics,
+            "gpu": self.gpu_metrics,
+            "memory": self.memory_stats,
+            "io": self.io_stats,
+            "battery": self.battery,
+            "accelerator": self.accelerator,
+            "power": {
+                "cpu": self.cpu_power_snapshot(),
+                "gpu": self.gpu_power_snapshot(),
+                "package": self.package_power_snapshot(),
+                "ane_w": self.ane_power,
+                "dram_w": self.dram_power,
+                "combined_w": self.combined_power,
+            },
+            "energy_wh": self.energy_wh,
+            "energy_impact": self.energy_impact,
+            "system_power_w": self.system_power().map(|(watts, _)| watts),
+            "dropped_samples": self.dropped_samples,
+        })
+    }
+
+    /// The next `--ndjson` row: the current report, or with
+    /// `--export-average N` the mean of every N reports and `None` between.
+    fn export_row(&mut self) -> Option<serde_json::Value> {
+        let Some(n) = self.config.export_average else {
+            return Some(self.report());
+        };
+        self.export_window.push(self.report());
+        if self.export_window.len() < n as usize {
+            return None;
+        }
+        let mut row = average_json(&self.export_window);
+        self.export_window.clear();
+        if let Some(fields) = row.as_object_mut() {
+            fields.insert("samples_averaged".into(), n.into());
+        }
+        Some(row)
+    }
+
+    fn power_frame(&self) -> PowerFrame {
+        PowerFrame {
+            cpu: self.cpu_power,
+            gpu: self.gpu_power,
+            ane: self.ane_power,
+            package: self.package_power,
+        }
+    }
+
+    /// Power values to display: the latest sample, or with `--refresh-rate`
+    /// a linear blend from the previous sample that reaches the latest one
+    /// a full sample interval after it arrived.
+    fn display_power(&self) -> PowerFrame {
+        let current = self.power_frame();
+        if self.config.refresh_rate.is_none() {
+            return current;
+        }
+        let t = self.sample_received.elapsed().as_secs_f32() / self.sample_seconds();
+        self.previous_power.lerp(current, t.clamp(0.0, 1.0))
+    }
+
+    fn snapshot(&self) -> UiSnapshot<'_> {
+        let display = self.display_power();
         UiSnapshot {
             soc: &self.soc,
             cpu: &self.cpu_metrics,
             gpu: &self.gpu_metrics,
             memory: &self.memory_stats,
             io: self.io_stats,
-            thermal_throttle,
-            color: self.color,
-            show_cores: self.config.show_cores,
+            net_io: !self.config.no_net_io,
+            disk_io: !self.config.no_disk_io,
+            thermal_throttle: self.throttled,
+            thermal_level: self.thermal_level,
+            power_alert: self.power_alert,
+            battery: self.battery,
+            gpu_memory_gb: self.accelerator.and_then(|stats| stats.in_use_memory_gb()),
+            status: self
+                .status_message
+                .as_ref()
+                .filter(|(_, at)| at.elapsed() < STATUS_TIMEOUT)
+                .map(|(message, _)| message.as_str()),
+            uptime: self.start.elapsed(),
+            dropped_samples: self.dropped_samples,
+            sample_interval: self.sample_duration,
+            requested_interval: Duration::from_millis(self.interval_ms),
+            auto_interval: self.config.auto_interval,
+            diagnostics: None,
+            sample_clock: self.last_timestamp.map(local_clock),
+            initialized: self.initialized,
+            theme: self.theme,
+            monochrome: self.config.monochrome(),
+            strings: Strings::for_lang(self.config.lang),
+            // A maximized processor panel has room for the full detail.
+            show_cores: self.config.show_cores || self.focus == Some(Panel::Processor),
+            panels: &self.panels,
+            focus: self.focus,
+            core_scroll: self.core_scroll,
+            core_scroll_limit: Cell::default(),
+            core_columns: self.config.core_columns as usize,
+            freq_unit: self.config.freq_unit,
+            net_unit: self.config.net_unit,
+            percent_basis: self.config.percent_basis,
+            bar_chars: self.config.bar_chars(),
+            ascii: self.config.ascii,
+            legend: self.config.legend,
+            load_color: !self.config.no_load_color,
             ane_percent: self.ane_percent,
-            ane_power_w: self.ane_power,
+            ane_power_w: display.ane,
             ram_has_swap: self.memory_stats.swap_total_gb >= 0.1,
             swap_used_gb: self.memory_stats.swap_used_gb,
             swap_total_gb: self.memory_stats.swap_total_gb,
             cpu_power: PowerSnapshot {
-                current: self.cpu_power,
-                average: self.cpu_avg.average(),
-                peak: self.cpu_peak,
-                percent_of_tdp: if self.soc.cpu_max_power > 0.0 {
-                    (self.cpu_power / self.soc.cpu_max_power * 100.0).clamp(0.0, 999.0)
-                } else {
-                    0.0
-                },
+                current: display.cpu,
+                ..self.cpu_power_snapshot()
             },
             gpu_power: PowerSnapshot {
-                current: self.gpu_power,
-                average: self.gpu_avg.average(),
-                peak: self.gpu_peak,
-                percent_of_tdp: if self.soc.gpu_max_power > 0.0 {
-                    (self.gpu_power / self.soc.gpu_max_power * 100.0).clamp(0.0, 999.0)
-                } else {
-                    0.0
-                },
+                current: display.gpu,
+                ..self.gpu_power_snapshot()
             },
+            dram_power_w: self.dram_power,
+            watts_per_ghz: self.watts_per_ghz,
+            combined_power_w: self.combined_power_divergence(),
             package_power: PowerSnapshot {
-                current: self.package_power,
-                average: self.package_avg.average(),
-                peak: self.package_peak,
-                percent_of_tdp: 0.0,
+                current: display.package,
+                ..self.package_power_snapshot()
             },
-            power_history: self.power_history.values(),
+            power_history: self.power_history.timeline(self.history_interval()),
+            history_cursor: self
+                .paused
+                .then(|| self.power_history.back(self.history_cursor))
+                .flatten()
+                .map(|(at, watts)| (local_clock(at), watts)),
+            thermal_history: self.thermal_history.timeline(self.history_interval()),
+            freq_history: self.freq_history(),
+            power_scale: self.config.power_scale,
+            watched: self
+                .config
+                .watch_process
+                .as_deref()
+                .map(|target| WatchedProcess {
+                    target,
+                    task: self.watched_task.as_ref(),
+                    seen: self.watched_seen,
+                }),
+            energy_wh: self.energy_wh,
+            energy_impact: self.energy_impact,
+            system_power: self.system_power(),
+            top_processes: self.show_top_processes.then_some(self.top_tasks.as_slice()),
+            history_seconds: (self.power_history.capacity() as f32 * self.sample_seconds()).round()
+                as u64,
+        }
+    }
+
+    fn record_session(&mut self) {
+        if let Some(mut summary) = self.session_summary.take() {
+            self.record_summary(&mut summary);
+            self.session_summary = Some(summary);
+        }
+    }
+
+    fn publish_web(&mut self) {
+        let Some(web) = &self.web else {
+            return;
+        };
+        let reading = self.last_reading.take().map(|mut reading| {
+            reading.host = Some(HostStats {
+                soc: self.soc.clone(),
+                memory: self.memory_stats.clone(),
+                io: self.io_stats,
+                battery: self.battery,
+                thermal_level: self.thermal_level,
+                accelerator: self.accelerator,
+            });
+            reading
+        });
+        web.publish(&self.report(), reading);
+    }
+
+    fn print_session_summary(&self) {
+        if let Some(summary) = &self.session_summary {
+            print!("{}", summary.render());
+        }
+    }
+
+    /// Best estimate of whole-machine power with where it came from:
+    /// powermetrics' system rail, else the power adapter or battery drain.
+    fn system_power(&self) -> Option<(f32, &'static str)> {
+        if let Some(watts) = self.reported_system_power {
+            return Some((watts, "powermetrics"));
+        }
+        let battery = self
+            .battery
+            .filter(|battery| battery.system_power_w > 0.0)?;
+        let source = if battery.external_power {
+            "adapter"
+        } else {
+            "battery"
+        };
+        Some((battery.system_power_w, source))
+    }
+
+    /// Whether `--duration` has run out.
+    fn duration_elapsed(&self) -> bool {
+        self.config
+            .duration
+            .is_some_and(|secs| self.start.elapsed() >= Duration::from_secs(secs))
+    }
+
+    fn record_summary(&self, summary: &mut Summary) {
+        summary.record(
+            self.cpu_power,
+            self.gpu_power,
+            self.package_power,
+            self.thermal_level,
+        );
+    }
+
+    fn refresh_thermal_level(&mut self, host: Option<&HostStats>) {
+        self.thermal_level = match host {
+            Some(host) => host.thermal_level,
+            None => read_warning_level(),
+        };
+        // An unmapped level says nothing either way; defer to powermetrics.
+        let throttled = self
+            .thermal_level
+            .filter(|level| !matches!(level, ThermalLevel::Unknown(_)))
+            .map(|level| level.is_throttled())
+            .unwrap_or_else(|| self.thermal_pressure.trim() != "Nominal");
+        if throttled && !self.throttled && self.config.bell_on_throttle {
+            // stderr, so the bell never lands in `--ndjson` or piped output.
+            let mut out = io::stderr();
+            let _ = out.write_all(b"\x07");
+            let _ = out.flush();
         }
+        self.throttled = throttled;
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(args: &[&str]) -> AppState {
+        let cli = Cli::parse_from(["asitop"].iter().chain(args));
+        let soc = SocInfo {
+            name: "Apple M1".into(),
+            e_core_count: 4,
+            p_core_count: 4,
+            gpu_core_count: 8,
+            cpu_max_power: 20.0,
+            gpu_max_power: 20.0,
+            ane_max_power: 8.0,
+        };
+        let mut memory_reader = MemoryReader::new(cli.mem_model);
+        AppState::new(cli, soc, &mut memory_reader)
+    }
+
+    fn reading() -> PowermetricsReading {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/two_cluster.plist"
+        );
+        let data = fs::read(path).unwrap();
+        data.split(|&b| b == 0)
+            .find_map(powermetrics::parse_chunk)
+            .unwrap()
+    }
+
+    /// The power-related part of `apply_reading`, without the IOKit reads.
+    fn feed(state: &mut AppState, reading: &PowermetricsReading) {
+        state.update_sample_duration(reading);
+        state.last_timestamp = Some(reading.timestamp);
+        state.cpu_metrics = reading.cpu.clone();
+        state.update_power_stats();
+    }
+
+    #[test]
+    fn power_uses_elapsed_ns_regardless_of_interval() {
+        // two_cluster: 2400 mJ CPU and 1200 mJ GPU over `elapsed_ns` = 2 s.
+        let reading = reading();
+        assert_eq!(reading.elapsed, Some(Duration::from_secs(2)));
+        let watts: Vec<(f32, f32)> = ["1", "7"]
+            .iter()
+            .map(|interval| {
+                let mut state = state(&["--interval", interval]);
+                feed(&mut state, &reading);
+                (state.cpu_power, state.gpu_power)
+            })
+            .collect();
+        assert_eq!(watts, [(1.2, 0.6), (1.2, 0.6)]);
+    }
+
+    #[test]
+    fn power_falls_back_to_timestamp_gap_then_interval() {
+        let mut reading = reading();
+        reading.elapsed = None;
+        let mut state = state(&["--interval", "4"]);
+
+        // No previous timestamp yet: only `--interval` is known.
+        feed(&mut state, &reading);
+        assert_eq!(state.sample_duration, Duration::from_secs(4));
+        assert_eq!(state.cpu_power, 0.6);
+
+        // Then the gap between sample timestamps wins over `--interval`.
+        reading.timestamp += Duration::from_secs(3);
+        feed(&mut state, &reading);
+        assert_eq!(state.sample_duration, Duration::from_secs(3));
+        assert_eq!(state.cpu_power, 0.8);
+    }
+
+    #[test]
+    fn consistent_combined_power_is_not_a_divergence() {
+        // two_cluster: `combined_power` 1800 mW against 2400 + 1200 mJ over 2 s.
+        let reading = reading();
+        let mut state = state(&["--interval", "2"]);
+        feed(&mut state, &reading);
+        assert_eq!(state.combined_power_divergence(), None);
+    }
+
+    #[test]
+    fn core_scroll_stops_at_renderer_limit() {
+        let mut state = state(&[]);
+        state.clamp_core_scroll(2);
+        state.scroll_cores(CORE_SCROLL_PAGE as isize);
+        assert_eq!(state.core_scroll, 2);
+        // Presses past the last row are not banked.
+        state.scroll_cores(-1);
+        assert_eq!(state.core_scroll, 1);
+
+        // A taller terminal lowers the limit and pulls the offset back.
+        state.clamp_core_scroll(0);
+        assert_eq!(state.core_scroll, 0);
+    }
+
+    #[test]
+    fn year_long_summary_is_sized_without_overflow() {
+        // 630M samples at 50 ms; the p95 window is capped and grows lazily.
+        let state = state(&["--interval-ms", "50", "--duration", "31536000", "--summary"]);
+        assert!(state.session_summary.is_some());
+        for duration in ["0", "31536001", "18446744073709551615"] {
+            assert!(Cli::try_parse_from(["asitop", "--duration", duration]).is_err());
+        }
+    }
+
+    #[test]
+    fn history_is_bounded_to_a_day() {
+        let state = state(&["--interval-ms", "50", "--history", "86400"]);
+        assert_eq!(state.power_history.capacity(), 1_728_000);
+        for history in ["0", "86401", "18446744073709551615"] {
+            assert!(Cli::try_parse_from(["asitop", "--history", history]).is_err());
+        }
+    }
+
+    #[test]
+    fn load_thresholds_are_checked_at_parse_time() {
+        let cli = Cli::parse_from(["asitop", "--load-thresholds", "50,80,90,95"]);
+        assert_eq!(cli.load_thresholds(), [50, 80, 90, 95]);
+        for thresholds in [
+            "50,80,90",
+            "50,80,90,95,99",
+            "50,40,90,95",
+            "50,80,90,101",
+            "a,b,c,d",
+        ] {
+            assert!(Cli::try_parse_from(["asitop", "--load-thresholds", thresholds]).is_err());
+        }
+    }
+
+    #[test]
+    fn accent_cycle_starts_after_current_accent() {
+        // The dark theme's green accent is palette entry 2.
+        let mut themed = state(&[]);
+        assert_eq!(themed.cycle_accent(), 3);
 
-    fn refresh_thermal_level(&mut self) {
-        self.thermal_level = read_warning_level();
+        let mut flagged = state(&["--color", "8"]);
+        assert_eq!(flagged.cycle_accent(), 0);
+        assert_eq!(flagged.cycle_accent(), 1);
     }
 }
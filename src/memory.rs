@@ -1,12 +1,14 @@
+use crate::config::MemModel;
 use libc::{
     self, HOST_VM_INFO64, HOST_VM_INFO64_COUNT, KERN_SUCCESS, c_int, c_void, host_statistics64,
     integer_t, mach_msg_type_number_t, mach_port_t, vm_statistics64,
 };
+use serde::{Deserialize, Serialize};
 use std::{mem, ptr, time::{Duration, Instant}};
 
 const SWAP_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryStats {
     pub total_gb: f32,
     pub used_gb: f32,
@@ -20,12 +22,13 @@ pub struct MemoryReader {
     host_port: mach_port_t,
     page_size: u64,
     total_bytes: u64,
+    model: MemModel,
     cached_swap: (u64, u64),
     last_swap_update: Option<Instant>,
 }
 
 impl MemoryReader {
-    pub fn new() -> Self {
+    pub fn new(model: MemModel) -> Self {
         #[allow(deprecated)]
         let host_port = unsafe { libc::mach_host_self() };
         let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
@@ -38,6 +41,7 @@ impl MemoryReader {
                 4096
             },
             total_bytes,
+            model,
             cached_swap: (0, 0),
             last_swap_update: None,
         }
@@ -81,9 +85,19 @@ impl MemoryReader {
                 .saturating_add(wired)
                 .saturating_add(compressed)
         };
-        let used = total.saturating_sub(available);
+        let used = match self.model {
+            MemModel::Available => total.saturating_sub(available),
+            MemModel::Activity => {
+                let app = (stats.internal_page_count as u64)
+                    .saturating_sub(stats.purgeable_count as u64)
+                    * page_size;
+                app.saturating_add(wired)
+                    .saturating_add(compressed)
+                    .min(total)
+            }
+        };
         let used_percent = if total > 0 {
-            ((total.saturating_sub(available)) as f64 / total as f64 * 100.0)
+            (used as f64 / total as f64 * 100.0)
                 .clamp(0.0, 100.0)
                 .floor()
         } else {
@@ -1,58 +1,130 @@
+use crate::web::{HostStats, RemoteSource};
 use anyhow::{Context, Result};
 use plist::{self, Date};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::VecDeque,
+    collections::{HashSet, VecDeque},
     fs::{self, File},
     io::{Cursor, Read, Seek, SeekFrom},
+    path::Path,
     process::{Child, Command, Stdio},
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 const POWER_FILE_PREFIX: &str = "/tmp/asitop_powermetrics";
 const MAX_READ_BYTES: u64 = 1 * 1024 * 1024; // 1 MiB from EOF is enough for one sample
+/// Bytes of an unparseable chunk kept for the `--verbose` log.
+const FAILURE_PREVIEW_BYTES: usize = 512;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PowermetricsReading {
     pub timestamp: SystemTime,
+    /// Sample window reported by powermetrics (`elapsed_ns`), if present.
+    pub elapsed: Option<Duration>,
     pub thermal_pressure: String,
     pub cpu: CpuMetrics,
     pub gpu: GpuMetrics,
+    /// Per-process entries from the `tasks` sampler (empty unless enabled).
+    pub tasks: Vec<TaskMetrics>,
+    /// System-wide energy impact, as in Activity Monitor's Energy tab. Only
+    /// known while the `tasks` sampler runs.
+    pub energy_impact: Option<f32>,
+    /// Whole-machine draw in watts, on powermetrics versions that report a
+    /// system power rail.
+    pub system_power_w: Option<f32>,
+    /// The sending machine's own stats on samples received over `--connect`;
+    /// always `None` for locally parsed samples.
+    #[serde(default)]
+    pub host: Option<HostStats>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CpuMetrics {
     pub e_cluster_active: u64,
     pub e_cluster_freq_mhz: u64,
     pub p_cluster_active: u64,
     pub p_cluster_freq_mhz: u64,
+    /// Boost ceiling from the clusters' DVFS tables; 0 when unknown.
+    pub e_cluster_max_freq_mhz: u64,
+    pub p_cluster_max_freq_mhz: u64,
     pub e_cores: Vec<CoreMetrics>,
     pub p_cores: Vec<CoreMetrics>,
+    /// Power drawn by the E/P clusters in watts, when powermetrics reports
+    /// it per cluster. Otherwise only the aggregate `cpu_w` is known.
+    pub e_cluster_power_w: Option<f32>,
+    pub p_cluster_power_w: Option<f32>,
     pub cpu_w: f32,
     pub gpu_w: f32,
     pub ane_w: f32,
+    /// ANE residency in percent, when powermetrics reports it; otherwise
+    /// usage can only be estimated from `ane_w`.
+    pub ane_active_pct: Option<u64>,
+    /// Only reported by some powermetrics versions.
+    pub dram_w: Option<f32>,
+    /// powermetrics' own `combined_power`, kept as a cross-check against the
+    /// sum of the components.
     pub package_w: f32,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CoreMetrics {
     pub id: u32,
     pub active_pct: u64,
     pub freq_mhz: u64,
+    /// Powered down (parked) for essentially the whole sample, as opposed to
+    /// idling at its minimum frequency.
+    pub down: bool,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GpuMetrics {
+    /// Whether powermetrics reported GPU data in this sample.
+    pub available: bool,
     pub active_pct: u64,
     pub freq_mhz: u64,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskMetrics {
+    pub pid: i64,
+    pub name: String,
+    pub cpu_ms_per_s: f32,
+    pub gpu_ms_per_s: f32,
+    pub energy_impact: f32,
+}
+
+impl TaskMetrics {
+    /// CPU time as a percentage of one core.
+    pub fn cpu_percent(&self) -> f32 {
+        self.cpu_ms_per_s / 10.0
+    }
+
+    pub fn gpu_percent(&self) -> f32 {
+        self.gpu_ms_per_s / 10.0
+    }
+}
+
 #[derive(Debug, Deserialize)]
-struct RawSnapshot {
+pub(crate) struct RawSnapshot {
     timestamp: Date,
+    #[serde(default)]
+    elapsed_ns: Option<u64>,
     thermal_pressure: String,
     processor: RawProcessor,
-    gpu: RawGpu,
+    /// Missing when the gpu sampler produced no data.
+    #[serde(default)]
+    gpu: Option<RawGpu>,
+    #[serde(default)]
+    tasks: Vec<RawTask>,
+    /// Whole-machine power in mW; absent on most powermetrics versions.
+    #[serde(default, alias = "sys_power")]
+    system_power: Option<f64>,
+    /// Totals over every task, including ones too small to be listed.
+    #[serde(default)]
+    all_tasks: Option<RawTasks>,
+    /// ANE residency; only some powermetrics versions report it.
+    #[serde(default)]
+    ane: Option<RawAne>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -65,6 +137,8 @@ struct RawProcessor {
     #[serde(default)]
     gpu_energy: f64,
     #[serde(default)]
+    dram_energy: Option<f64>,
+    #[serde(default)]
     combined_power: f64,
 }
 
@@ -72,16 +146,33 @@ struct RawProcessor {
 struct RawCluster {
     name: String,
     freq_hz: f64,
-    idle_ratio: f64,
+    /// Missing on some powermetrics versions, which only report per core.
+    #[serde(default)]
+    idle_ratio: Option<f64>,
+    /// Cluster power in mW; only some SoCs report it.
+    #[serde(default, alias = "cpu_power")]
+    power: Option<f64>,
+    #[serde(default)]
+    dvfm_states: Vec<RawDvfmState>,
     #[serde(default)]
     cpus: Vec<RawCore>,
 }
 
+/// One DVFS operating point of a cluster; `freq` is normally in MHz.
+#[derive(Debug, Deserialize)]
+struct RawDvfmState {
+    freq: f64,
+}
+
 #[derive(Debug, Clone)]
 struct ClusterData {
     name: String,
-    active_pct: u64,
+    /// `None` when the cluster reported no usable idle ratio, as opposed to
+    /// `Some(0)` for a genuinely idle cluster.
+    active_pct: Option<u64>,
     freq_mhz: u64,
+    /// Highest DVFS state, or 0 when powermetrics lists none.
+    max_freq_mhz: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -89,6 +180,28 @@ struct RawCore {
     cpu: u32,
     freq_hz: f64,
     idle_ratio: f64,
+    #[serde(default)]
+    down_ratio: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTask {
+    #[serde(default)]
+    pid: i64,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    cputime_ms_per_s: f64,
+    #[serde(default)]
+    gputime_ms_per_s: f64,
+    #[serde(default)]
+    energy_impact_per_s: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTasks {
+    #[serde(default)]
+    energy_impact_per_s: f64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -97,14 +210,74 @@ struct RawGpu {
     idle_ratio: f64,
 }
 
+/// Reported either as an idle ratio, like the GPU, or as active residency.
+#[derive(Debug, Deserialize)]
+struct RawAne {
+    #[serde(default)]
+    idle_ratio: Option<f64>,
+    #[serde(default, alias = "active_residency")]
+    active_ratio: Option<f64>,
+}
+
+impl RawAne {
+    fn active_pct(&self) -> Option<u64> {
+        self.idle_ratio
+            .map(ratio_to_pct)
+            .or_else(|| self.active_ratio.map(|active| 100 - ratio_to_pct(active)))
+    }
+}
+
 pub fn powermetrics_path(timecode: &str) -> String {
     format!("{POWER_FILE_PREFIX}{timecode}")
 }
 
-pub fn run_powermetrics(timecode: &str, interval_ms: u64) -> Result<Child> {
-    cleanup_powermetrics_files().ok();
+/// Check up front that powermetrics can actually be started, so users get an
+/// actionable message instead of a timeout waiting for the first sample.
+pub fn preflight() -> Result<()> {
+    let on_path = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join("powermetrics").is_file()))
+        .unwrap_or(false);
+    if !on_path && !Path::new("/usr/bin/powermetrics").is_file() {
+        anyhow::bail!(
+            "powermetrics was not found on PATH or at /usr/bin/powermetrics; \
+             asitop needs macOS on Apple Silicon"
+        );
+    }
+
+    // SAFETY: geteuid has no preconditions.
+    if unsafe { libc::geteuid() } == 0 {
+        return Ok(());
+    }
+    // `-l` only asks whether the exact command is allowed; `-n` fails instead
+    // of prompting, since powermetrics runs with stdin detached.
+    let allowed = Command::new("sudo")
+        .args(["-n", "-l", "nice", "-n", "10", "powermetrics"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| anyhow::anyhow!("sudo is not available ({err}); run asitop as root"))?
+        .success();
+    if !allowed {
+        anyhow::bail!(
+            "powermetrics requires root; run with sudo (e.g. `sudo asitop_in_rust`) \
+             or configure a passwordless sudoers rule for powermetrics"
+        );
+    }
+    Ok(())
+}
+
+/// Spawn powermetrics writing plist samples to the timecoded file.
+/// `with_tasks` adds the per-process `tasks` sampler, which is noticeably
+/// heavier, so it is only enabled when a feature needs it.
+pub fn run_powermetrics(timecode: &str, interval_ms: u64, with_tasks: bool) -> Result<Child> {
     let path = powermetrics_path(timecode);
     let interval_arg = interval_ms.to_string();
+    let samplers = if with_tasks {
+        "cpu_power,gpu_power,thermal,tasks"
+    } else {
+        "cpu_power,gpu_power,thermal"
+    };
     let mut cmd = Command::new("sudo");
     cmd.args([
         "nice",
@@ -112,17 +285,20 @@ pub fn run_powermetrics(timecode: &str, interval_ms: u64) -> Result<Child> {
         "10",
         "powermetrics",
         "--samplers",
-        "cpu_power,gpu_power,thermal",
+        samplers,
         "-o",
         &path,
         "-f",
         "plist",
         "-i",
         &interval_arg,
-    ])
-    .stdin(Stdio::null())
-    .stdout(Stdio::null())
-    .stderr(Stdio::null());
+    ]);
+    if with_tasks {
+        cmd.args(["--show-process-energy", "--show-process-gpu"]);
+    }
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
 
     cmd.spawn().with_context(|| "failed to spawn powermetrics")
 }
@@ -149,104 +325,415 @@ pub fn new_timecode() -> String {
     now.to_string()
 }
 
-/// Cached reader for powermetrics file to reduce unnecessary I/O
+/// Incremental reader for the powermetrics output file. Only bytes appended
+/// since the previous call are read; an unterminated trailing chunk is kept
+/// in `buffer` until powermetrics finishes writing it.
 pub struct PowermetricsReader {
     path: String,
-    last_len: u64,
+    /// File offset up to which bytes have been consumed into `buffer`.
+    offset: u64,
+    /// Bytes after the last NUL separator seen so far.
     buffer: Vec<u8>,
+    /// Length of the trailing chunk if it already parsed before its NUL
+    /// separator arrived, so it is not reported twice.
+    tail_parsed_len: Option<usize>,
+    /// Raw plist bytes of the most recent successfully parsed sample.
+    last_chunk: Vec<u8>,
+    stats: ReaderStats,
+    /// Set by `from_file`: samples come from a saved dump instead of a live
+    /// powermetrics process.
+    replay: Option<Replay>,
+    /// Set by `connect`: samples come from another asitop's `--web` server.
+    remote: Option<RemoteSource>,
+    /// Why the newest complete chunk failed to parse, while it keeps failing.
+    failure: Option<ParseFailure>,
+    /// Whether the current run of failures has not been reported yet.
+    failure_unreported: bool,
+}
+
+/// A complete powermetrics sample that could not be parsed, e.g. after a
+/// key changed type in a new macOS release.
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+    /// The plist/serde error, or why the decoded sample was rejected.
+    pub error: String,
+    /// The chunk's first `FAILURE_PREVIEW_BYTES`, lossily decoded.
+    pub preview: String,
+}
+
+impl ParseFailure {
+    fn diagnose(chunk: &[u8]) -> Self {
+        let error = match plist::from_reader::<_, RawSnapshot>(Cursor::new(chunk)) {
+            Err(err) => err.to_string(),
+            Ok(_) => "sample has neither CPU clusters nor a GPU frequency".to_string(),
+        };
+        let preview = &chunk[..chunk.len().min(FAILURE_PREVIEW_BYTES)];
+        Self {
+            error,
+            preview: String::from_utf8_lossy(preview).into_owned(),
+        }
+    }
+}
+
+/// Samples of a saved powermetrics dump, handed out one per interval.
+struct Replay {
+    samples: Vec<(PowermetricsReading, Vec<u8>)>,
+    next: usize,
+    interval: Duration,
+    next_due: Instant,
+    looping: bool,
+    finished: bool,
+    /// Added to replayed timestamps so they keep increasing across loops.
+    shift: Duration,
+    /// Time covered by one pass over the dump.
+    span: Duration,
+}
+
+impl Replay {
+    fn next(&mut self, last_chunk: &mut Vec<u8>) -> Option<PowermetricsReading> {
+        if self.finished || Instant::now() < self.next_due {
+            return None;
+        }
+        if self.next == self.samples.len() {
+            if !self.looping {
+                self.finished = true;
+                return None;
+            }
+            self.next = 0;
+            self.shift += self.span;
+        }
+        let (reading, raw) = &self.samples[self.next];
+        self.next += 1;
+        self.next_due = Instant::now() + self.interval;
+        last_chunk.clone_from(raw);
+        let mut reading = reading.clone();
+        reading.timestamp += self.shift;
+        Some(reading)
+    }
+}
+
+/// Parser diagnostics shown in the verbose overlay.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReaderStats {
+    /// Size of the powermetrics output file at the last read.
+    pub file_len: u64,
+    /// Time spent reading and parsing on the last read.
+    pub parse_time: Duration,
+    /// Null-delimited plist chunks found in the newly read bytes.
+    pub chunks: usize,
 }
 
 impl PowermetricsReader {
     pub fn new(timecode: &str) -> Self {
         Self {
             path: powermetrics_path(timecode),
-            last_len: 0,
-            buffer: Vec::with_capacity(MAX_READ_BYTES as usize),
+            offset: 0,
+            buffer: Vec::with_capacity(64 * 1024),
+            tail_parsed_len: None,
+            last_chunk: Vec::new(),
+            stats: ReaderStats::default(),
+            replay: None,
+            remote: None,
+            failure: None,
+            failure_unreported: false,
+        }
+    }
+
+    /// Read samples from a remote asitop started with `--web`, polled over
+    /// HTTP at `addr` (`host:port`).
+    pub fn connect(addr: &str, interval: Duration) -> Self {
+        let mut reader = Self::new("remote");
+        reader.remote = Some(RemoteSource::new(addr, interval));
+        reader
+    }
+
+    /// Replay a saved powermetrics plist dump, yielding one sample every
+    /// `interval`. At the end it starts over if `looping`, otherwise
+    /// `finished` turns true.
+    pub fn from_file(path: &Path, interval: Duration, looping: bool) -> Result<Self> {
+        let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let samples: Vec<(PowermetricsReading, Vec<u8>)> = data
+            .split(|b| *b == 0)
+            .filter(|chunk| !chunk.iter().all(u8::is_ascii_whitespace))
+            .filter_map(|chunk| parse_chunk(chunk).map(|reading| (reading, chunk.to_vec())))
+            .collect();
+        let (Some((first, _)), Some((last, _))) = (samples.first(), samples.last()) else {
+            anyhow::bail!("no powermetrics samples found in {}", path.display());
+        };
+        let span = last
+            .timestamp
+            .duration_since(first.timestamp)
+            .unwrap_or_default()
+            + interval;
+
+        let mut reader = Self::new("replay");
+        reader.stats = ReaderStats {
+            file_len: data.len() as u64,
+            parse_time: Duration::ZERO,
+            chunks: samples.len(),
+        };
+        reader.replay = Some(Replay {
+            samples,
+            next: 0,
+            interval,
+            next_due: Instant::now(),
+            looping,
+            finished: false,
+            shift: Duration::ZERO,
+            span,
+        });
+        Ok(reader)
+    }
+
+    /// Why the last `--connect` poll failed, while the remote is unreachable.
+    pub fn remote_error(&self) -> Option<&str> {
+        self.remote.as_ref().and_then(RemoteSource::error)
+    }
+
+    /// Whether a non-looping replay has run out of samples.
+    pub fn finished(&self) -> bool {
+        self.replay.as_ref().is_some_and(|replay| replay.finished)
+    }
+
+    pub fn stats(&self) -> ReaderStats {
+        self.stats
+    }
+
+    /// The current parse failure, if complete samples are failing to parse.
+    pub fn failure(&self) -> Option<&ParseFailure> {
+        self.failure.as_ref()
+    }
+
+    /// The current parse failure, once per run of failing samples.
+    pub fn take_failure(&mut self) -> Option<ParseFailure> {
+        if !self.failure_unreported {
+            return None;
         }
+        self.failure_unreported = false;
+        self.failure.clone()
+    }
+
+    /// Raw plist of the last sample returned by `parse`.
+    pub fn last_chunk(&self) -> &[u8] {
+        &self.last_chunk
     }
 
     pub fn set_timecode(&mut self, timecode: &str) {
         self.path = powermetrics_path(timecode);
-        self.last_len = 0;
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.offset = 0;
+        self.buffer.clear();
+        self.tail_parsed_len = None;
     }
 
     pub fn parse(&mut self) -> Result<Option<PowermetricsReading>> {
+        if let Some(replay) = self.replay.as_mut() {
+            return Ok(replay.next(&mut self.last_chunk));
+        }
+        if let Some(remote) = self.remote.as_mut() {
+            return remote.next();
+        }
+
         let mut file = match File::open(&self.path) {
             Ok(f) => f,
             Err(_) => return Ok(None),
         };
 
         let len = file.metadata().map(|m| m.len()).unwrap_or(0);
-        if len == 0 {
-            return Ok(None);
+        if len < self.offset {
+            // Truncated or replaced underneath us; start over.
+            self.reset();
         }
-
-        // Skip if file size hasn't changed
-        if len == self.last_len {
+        // Skip if nothing was appended
+        if len == 0 || len == self.offset {
             return Ok(None);
         }
-        self.last_len = len;
+        self.stats.file_len = len;
+        let started = Instant::now();
 
-        let start = len.saturating_sub(MAX_READ_BYTES);
-        if file.seek(SeekFrom::Start(start)).is_err() {
-            file.seek(SeekFrom::Start(0)).ok();
+        // On the first read only the newest samples matter.
+        if self.offset == 0 {
+            self.offset = len.saturating_sub(MAX_READ_BYTES);
+        }
+        if file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Ok(None);
         }
 
-        self.buffer.clear();
+        let before = self.buffer.len();
         if let Err(e) = file.read_to_end(&mut self.buffer) {
-            if self.buffer.is_empty() {
+            if self.buffer.len() == before {
                 return Err(anyhow::anyhow!("failed to read powermetrics chunk: {}", e));
             }
         }
+        self.offset += (self.buffer.len() - before) as u64;
 
-        if self.buffer.is_empty() {
-            return Ok(None);
+        // A separator should arrive well within this window; if not, drop the
+        // oldest bytes rather than growing without bound.
+        if self.buffer.len() > MAX_READ_BYTES as usize {
+            let excess = self.buffer.len() - MAX_READ_BYTES as usize;
+            self.buffer.drain(..excess);
+            self.tail_parsed_len = None;
         }
 
-        for chunk in self.buffer.split(|b| *b == 0).rev().filter(|c| !c.is_empty()) {
-            if let Ok(snapshot) = plist::from_reader::<_, RawSnapshot>(Cursor::new(chunk)) {
-                return Ok(Some(convert_snapshot(snapshot)));
+        let mut reading = None;
+        let mut chunk_count = 0;
+        let mut failure = None;
+        let tail_start = match self.buffer.iter().rposition(|b| *b == 0) {
+            Some(last_nul) => {
+                let chunks: Vec<&[u8]> = self.buffer[..last_nul].split(|b| *b == 0).collect();
+                let skip_first = self.tail_parsed_len.take() == chunks.first().map(|c| c.len());
+                chunk_count = chunks.iter().filter(|c| !c.is_empty()).count();
+                for (index, chunk) in chunks.iter().enumerate().rev() {
+                    if chunk.is_empty() || (index == 0 && skip_first) {
+                        continue;
+                    }
+                    if let Some(parsed) = parse_chunk(chunk) {
+                        reading = Some(parsed);
+                        self.last_chunk.clear();
+                        self.last_chunk.extend_from_slice(chunk);
+                        break;
+                    }
+                    // Complete chunks should always parse; keep the newest
+                    // failure in case none of them does.
+                    if failure.is_none() {
+                        failure = Some(ParseFailure::diagnose(chunk));
+                    }
+                }
+                last_nul + 1
+            }
+            None => 0,
+        };
+        self.buffer.drain(..tail_start);
+
+        // powermetrics may not terminate the newest sample until the next one
+        // is written, so try the unterminated tail as well.
+        if reading.is_none() && !self.buffer.is_empty() && self.tail_parsed_len.is_none() {
+            chunk_count += 1;
+            if let Some(parsed) = parse_chunk(&self.buffer) {
+                reading = Some(parsed);
+                self.tail_parsed_len = Some(self.buffer.len());
+                self.last_chunk.clone_from(&self.buffer);
             }
         }
-        Ok(None)
+
+        if reading.is_some() {
+            self.failure = None;
+        } else if let Some(failure) = failure {
+            // Report once per run of failures rather than every sample.
+            self.failure_unreported |= self.failure.is_none();
+            self.failure = Some(failure);
+        }
+
+        self.stats.chunks = chunk_count;
+        self.stats.parse_time = started.elapsed();
+        Ok(reading)
     }
 }
 
-fn convert_snapshot(raw: RawSnapshot) -> PowermetricsReading {
+pub(crate) fn parse_chunk(chunk: &[u8]) -> Option<PowermetricsReading> {
+    plist::from_reader::<_, RawSnapshot>(Cursor::new(chunk))
+        .ok()
+        .and_then(convert_snapshot)
+}
+
+/// `combined_power` in mW, or the sum of the component energies over the
+/// sample window on powermetrics versions that leave it out. DRAM is included
+/// so the fallback matches the headline package figure and never reads as a
+/// divergence. Without `elapsed_ns` there is no window to average over, and
+/// the cross-check is skipped.
+fn combined_power_mw(processor: &RawProcessor, elapsed_ns: Option<u64>) -> f64 {
+    if processor.combined_power > 0.0 {
+        return processor.combined_power;
+    }
+    let Some(seconds) = elapsed_ns.filter(|ns| *ns > 0).map(|ns| ns as f64 / 1e9) else {
+        return 0.0;
+    };
+    (processor.cpu_energy
+        + processor.gpu_energy
+        + processor.ane_energy
+        + processor.dram_energy.unwrap_or(0.0))
+        / seconds
+}
+
+/// Returns `None` for snapshots with neither CPU clusters nor a GPU frequency,
+/// which powermetrics occasionally emits mid-write; callers keep the previous
+/// reading instead of flashing zeros.
+///
+/// Power values are narrowed from the plist's `f64` to `f32` here, once;
+/// `AppState`, `PowerSnapshot` and `UiSnapshot` all stay in `f32`.
+pub(crate) fn convert_snapshot(raw: RawSnapshot) -> Option<PowermetricsReading> {
+    let gpu_freq = raw.gpu.as_ref().map_or(0, |gpu| display_freq(gpu.freq_hz));
+    if raw.processor.clusters.is_empty() && gpu_freq == 0 {
+        return None;
+    }
+
     let timestamp = raw.timestamp.into();
+    let energy_impact = energy_impact(raw.all_tasks.as_ref(), &raw.tasks);
+    let combined_mw = combined_power_mw(&raw.processor, raw.elapsed_ns);
     let mut e_clusters: Vec<ClusterData> = Vec::new();
     let mut p_clusters: Vec<ClusterData> = Vec::new();
     let mut e_cores = Vec::new();
     let mut p_cores = Vec::new();
+    let mut e_power_mw: Option<f64> = None;
+    let mut p_power_mw: Option<f64> = None;
+    // Apple Silicon has no SMT, so each logical CPU id should appear once;
+    // a repeated id would double-count a core and shift the E01/P01 labels.
+    let mut seen_cpus = HashSet::new();
 
     for cluster in raw.processor.clusters {
         let RawCluster {
             name,
             freq_hz,
             idle_ratio,
+            power,
+            dvfm_states,
             cpus,
         } = cluster;
         let freq_mhz = display_freq(freq_hz);
-        let active = ratio_to_pct(idle_ratio);
+        let max_freq_mhz = dvfm_states
+            .iter()
+            .map(|state| display_freq(state.freq))
+            .max()
+            .unwrap_or(0);
+        let active = idle_ratio
+            .filter(|ratio| ratio.is_finite())
+            .map(ratio_to_pct);
         let is_e = name.starts_with(['E', 'e']);
+        if let Some(mw) = power.filter(|mw| mw.is_finite() && *mw >= 0.0) {
+            let total = if is_e {
+                &mut e_power_mw
+            } else {
+                &mut p_power_mw
+            };
+            *total = Some(total.unwrap_or(0.0) + mw);
+        }
         if is_e {
             e_clusters.push(ClusterData {
                 name: name.clone(),
                 active_pct: active,
                 freq_mhz,
+                max_freq_mhz,
             });
         } else if name.starts_with(['P', 'p']) {
             p_clusters.push(ClusterData {
                 name: name.clone(),
                 active_pct: active,
                 freq_mhz,
+                max_freq_mhz,
             });
         }
         for core in cpus {
+            if !seen_cpus.insert(core.cpu) {
+                continue;
+            }
             let metrics = CoreMetrics {
                 id: core.cpu,
                 active_pct: ratio_to_pct(core.idle_ratio),
                 freq_mhz: display_freq(core.freq_hz),
+                down: core.down_ratio.is_finite() && core.down_ratio >= CORE_DOWN_RATIO,
             };
             if is_e {
                 e_cores.push(metrics);
@@ -258,37 +745,102 @@ fn convert_snapshot(raw: RawSnapshot) -> PowermetricsReading {
 
     let (e_cluster_active, e_cluster_freq) = aggregate_cluster(&e_clusters, &e_cores, 'E');
     let (p_cluster_active, p_cluster_freq) = aggregate_cluster(&p_clusters, &p_cores, 'P');
+    let max_freq = |clusters: &[ClusterData]| clusters.iter().map(|c| c.max_freq_mhz).max();
 
-    PowermetricsReading {
+    Some(PowermetricsReading {
         timestamp,
+        elapsed: raw
+            .elapsed_ns
+            .filter(|ns| *ns > 0)
+            .map(Duration::from_nanos),
         thermal_pressure: raw.thermal_pressure,
         cpu: CpuMetrics {
             e_cluster_active,
             e_cluster_freq_mhz: e_cluster_freq,
             p_cluster_active,
             p_cluster_freq_mhz: p_cluster_freq,
+            e_cluster_max_freq_mhz: max_freq(&e_clusters).unwrap_or(0),
+            p_cluster_max_freq_mhz: max_freq(&p_clusters).unwrap_or(0),
             e_cores,
             p_cores,
+            e_cluster_power_w: e_power_mw.map(|mw| (mw / 1000.0) as f32),
+            p_cluster_power_w: p_power_mw.map(|mw| (mw / 1000.0) as f32),
             cpu_w: (raw.processor.cpu_energy / 1000.0) as f32,
             gpu_w: (raw.processor.gpu_energy / 1000.0) as f32,
             ane_w: (raw.processor.ane_energy / 1000.0) as f32,
-            package_w: (raw.processor.combined_power / 1000.0) as f32,
-        },
-        gpu: GpuMetrics {
-            active_pct: ratio_to_pct(raw.gpu.idle_ratio),
-            freq_mhz: display_freq(raw.gpu.freq_hz),
+            ane_active_pct: raw.ane.as_ref().and_then(RawAne::active_pct),
+            dram_w: raw.processor.dram_energy.map(|mj| (mj / 1000.0) as f32),
+            package_w: (combined_mw / 1000.0) as f32,
         },
+        gpu: raw
+            .gpu
+            .map(|gpu| GpuMetrics {
+                available: true,
+                active_pct: ratio_to_pct(gpu.idle_ratio),
+                freq_mhz: gpu_freq,
+            })
+            .unwrap_or_default(),
+        tasks: raw
+            .tasks
+            .into_iter()
+            .map(|task| TaskMetrics {
+                pid: task.pid,
+                name: task.name,
+                cpu_ms_per_s: task.cputime_ms_per_s as f32,
+                gpu_ms_per_s: task.gputime_ms_per_s as f32,
+                energy_impact: task.energy_impact_per_s as f32,
+            })
+            .collect(),
+        energy_impact,
+        system_power_w: raw
+            .system_power
+            .filter(|mw| mw.is_finite() && *mw > 0.0)
+            .map(|mw| (mw / 1000.0) as f32),
+        host: None,
+    })
+}
+
+/// Prefer powermetrics' own `all_tasks` total; older versions only list the
+/// individual tasks, so sum those instead.
+fn energy_impact(all_tasks: Option<&RawTasks>, tasks: &[RawTask]) -> Option<f32> {
+    if let Some(all) = all_tasks {
+        return Some(all.energy_impact_per_s as f32);
     }
+    if tasks.is_empty() {
+        return None;
+    }
+    let total: f64 = tasks.iter().map(|task| task.energy_impact_per_s).sum();
+    Some(total as f32)
 }
 
+/// Fraction of a sample a core must spend powered down to be shown as off.
+const CORE_DOWN_RATIO: f64 = 0.95;
+
+/// Plausible Apple Silicon clock range in MHz, used to infer the unit of
+/// `freq_hz` (Hz, kHz or MHz depending on the powermetrics version).
+const PLAUSIBLE_FREQ_MHZ: (f64, f64) = (600.0, 4000.0);
+
 fn display_freq(freq_hz: f64) -> u64 {
     if !freq_hz.is_finite() || freq_hz <= 0.0 {
-        0
-    } else if freq_hz >= 100_000.0 {
-        (freq_hz / 1_000_000.0).round() as u64
-    } else {
-        freq_hz.round() as u64
+        return 0;
     }
+    let (min, max) = PLAUSIBLE_FREQ_MHZ;
+    // How far (as a ratio) a candidate lands outside the plausible range.
+    let distance = |mhz: f64| {
+        if mhz < min {
+            min / mhz
+        } else if mhz > max {
+            mhz / max
+        } else {
+            1.0
+        }
+    };
+    // GPUs idle below the range, so take the nearest interpretation rather
+    // than requiring an exact fit. Ties prefer Hz, the documented unit.
+    [freq_hz / 1_000_000.0, freq_hz / 1_000.0, freq_hz]
+        .into_iter()
+        .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+        .map_or(0, |mhz| mhz.round() as u64)
 }
 
 fn ratio_to_pct(idle_ratio: f64) -> u64 {
@@ -304,17 +856,14 @@ fn ratio_to_pct(idle_ratio: f64) -> u64 {
     ((1.0 - ratio) * 100.0).round() as u64
 }
 
+/// Cluster usage comes from the average of its cores when any are listed,
+/// then from the cluster's own idle ratio, and is 0 only when neither exists.
+/// Frequency prefers the cluster figure over the fastest core.
 fn aggregate_cluster(clusters: &[ClusterData], cores: &[CoreMetrics], prefix: char) -> (u64, u64) {
-    let core_avg = core_average(cores);
     let core_freq = core_max_freq(cores);
     let (cluster_active, cluster_freq) = cluster_stats(clusters, prefix);
 
-    let active = if core_avg > 0 {
-        core_avg
-    } else {
-        cluster_active.unwrap_or(0)
-    };
-
+    let active = core_average(cores).or(cluster_active).unwrap_or(0);
     let freq = cluster_freq.unwrap_or(core_freq);
 
     (active, freq)
@@ -323,7 +872,7 @@ fn aggregate_cluster(clusters: &[ClusterData], cores: &[CoreMetrics], prefix: ch
 fn cluster_stats(clusters: &[ClusterData], prefix: char) -> (Option<u64>, Option<u64>) {
     let primary_label = format!("{prefix}-Cluster");
     if let Some(primary) = clusters.iter().find(|c| c.name == primary_label) {
-        let active = (primary.active_pct > 0).then_some(primary.active_pct);
+        let active = primary.active_pct;
         let freq = (primary.freq_mhz > 0).then_some(primary.freq_mhz);
         return (active, freq);
     }
@@ -336,10 +885,12 @@ fn cluster_stats(clusters: &[ClusterData], prefix: char) -> (Option<u64>, Option
     // Guard against division by zero - matching.len() is checked to be non-empty
     let matching_len = matching.len();
     if matching_len > 0 {
-        let active_sum: u64 = matching.iter().map(|c| c.active_pct).sum();
+        let reported: Vec<u64> = matching.iter().filter_map(|c| c.active_pct).collect();
         let freq_max = matching.iter().map(|c| c.freq_mhz).max().unwrap_or(0);
-        // Safe division: matching_len is guaranteed > 0 here
-        let active = (active_sum > 0).then_some(active_sum / matching_len as u64);
+        // Average only the clusters that reported usage; none reporting is
+        // unknown rather than idle.
+        let active =
+            (!reported.is_empty()).then(|| reported.iter().sum::<u64>() / reported.len() as u64);
         let freq = (freq_max > 0).then_some(freq_max);
         return (active, freq);
     }
@@ -347,12 +898,12 @@ fn cluster_stats(clusters: &[ClusterData], prefix: char) -> (Option<u64>, Option
     (None, None)
 }
 
-fn core_average(cores: &[CoreMetrics]) -> u64 {
+fn core_average(cores: &[CoreMetrics]) -> Option<u64> {
     if cores.is_empty() {
-        0
+        None
     } else {
         let sum: u64 = cores.iter().map(|c| c.active_pct).sum();
-        sum / cores.len() as u64
+        Some(sum / cores.len() as u64)
     }
 }
 
@@ -360,33 +911,77 @@ fn core_max_freq(cores: &[CoreMetrics]) -> u64 {
     cores.iter().map(|c| c.freq_mhz).max().unwrap_or(0)
 }
 
-/// Helper storing datapoints for sparkline-style history charts.
+/// Helper storing timestamped datapoints for sparkline-style history charts.
 #[derive(Default)]
-pub struct History {
-    data: VecDeque<f32>,
+pub struct History<T = f32> {
+    data: VecDeque<(SystemTime, T)>,
     max_len: usize,
 }
 
-impl History {
+impl<T: Copy> History<T> {
+    /// Grows as samples arrive; a day of `--history` at a short interval is
+    /// too much to reserve for every buffer up front.
     pub fn new(max_len: usize) -> Self {
         Self {
-            data: VecDeque::with_capacity(max_len),
+            data: VecDeque::new(),
             max_len,
         }
     }
 
-    pub fn push(&mut self, value: f32) {
+    pub fn push(&mut self, at: SystemTime, value: T) {
         if self.data.len() == self.max_len {
             self.data.pop_front();
         }
-        self.data.push_back(value);
+        self.data.push_back((at, value));
+    }
+
+    /// The stored values, one slot per `interval`, with `None` filling the
+    /// slots of samples that never arrived so stalls show up as gaps. Only
+    /// the newest `capacity()` slots are returned.
+    pub fn timeline(&self, interval: Duration) -> Vec<Option<T>> {
+        let interval = interval.as_secs_f64().max(0.001);
+        let mut slots = Vec::with_capacity(self.data.len());
+        let mut previous: Option<SystemTime> = None;
+        for (at, value) in &self.data {
+            if let Some(gap) = previous.and_then(|last| at.duration_since(last).ok()) {
+                let missing = (gap.as_secs_f64() / interval).round() as usize;
+                let missing = missing.saturating_sub(1).min(self.max_len);
+                slots.extend(std::iter::repeat_n(None, missing));
+            }
+            slots.push(Some(*value));
+            previous = Some(*at);
+        }
+        let start = slots.len().saturating_sub(self.max_len);
+        slots.split_off(start)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.max_len
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
     }
 
-    pub fn values(&self) -> Vec<f32> {
-        self.data.iter().copied().collect()
+    /// The sample `offset` places before the newest one (0 = newest).
+    pub fn back(&self, offset: usize) -> Option<(SystemTime, T)> {
+        let index = self.data.len().checked_sub(offset + 1)?;
+        self.data.get(index).copied()
     }
 }
 
+/// Minimum number of samples in a rolling window. A single-sample window would
+/// make the "average" identical to the current reading.
+const MIN_ROLLING_WINDOW: usize = 2;
+
+/// Number of samples needed to cover `window_secs` at the given sample
+/// interval, rounded to the nearest sample (e.g. 30s at 4000ms -> 8 samples).
+pub fn rolling_window(window_secs: u64, interval_ms: u64) -> usize {
+    let interval = interval_ms.max(1) as f64;
+    let samples = (window_secs as f64 * 1000.0 / interval).round() as usize;
+    samples.max(MIN_ROLLING_WINDOW)
+}
+
 #[derive(Default)]
 pub struct RollingAverage {
     data: VecDeque<f32>,
@@ -396,9 +991,14 @@ pub struct RollingAverage {
 }
 
 impl RollingAverage {
+    /// A zero-length window would drop every sample and average to 0
+    /// forever, so it is treated as a window of one (the latest reading).
+    /// The buffer grows as samples arrive rather than reserving the whole
+    /// window up front, which can be large for `--summary` runs.
     pub fn new(max_len: usize) -> Self {
+        let max_len = max_len.max(1);
         Self {
-            data: VecDeque::with_capacity(max_len),
+            data: VecDeque::new(),
             max_len,
             sum: 0.0,
             push_count: 0,
@@ -406,9 +1006,6 @@ impl RollingAverage {
     }
 
     pub fn push(&mut self, value: f32) {
-        if self.max_len == 0 {
-            return;
-        }
         if self.data.len() == self.max_len {
             if let Some(front) = self.data.pop_front() {
                 self.sum -= front;
@@ -432,4 +1029,256 @@ impl RollingAverage {
             self.sum / self.data.len() as f32
         }
     }
+
+    /// Nearest-rank percentile (`p` in 0..=100) of the current window.
+    /// Sorts a copy, which is fine for the few hundred samples a window holds.
+    pub fn percentile(&self, p: f32) -> f32 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<f32> = self.data.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+        sorted[rank.saturating_sub(1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// First sample of a NUL-separated fixture under `tests/fixtures`.
+    fn fixture_chunk(name: &str) -> Vec<u8> {
+        let path = format!("{}/tests/fixtures/{name}.plist", env!("CARGO_MANIFEST_DIR"));
+        let data = fs::read(&path).unwrap();
+        data.split(|&b| b == 0)
+            .find(|chunk| chunk.iter().any(|b| !b.is_ascii_whitespace()))
+            .unwrap()
+            .to_vec()
+    }
+
+    fn fixture(name: &str) -> PowermetricsReading {
+        parse_chunk(&fixture_chunk(name)).unwrap()
+    }
+
+    /// A reader tailing a scratch powermetrics file holding `first`.
+    fn tailing(name: &str, first: &[u8]) -> PowermetricsReader {
+        let reader = PowermetricsReader::new(&format!("-test-{}-{name}", std::process::id()));
+        fs::write(&reader.path, [first, b"\0"].concat()).unwrap();
+        reader
+    }
+
+    fn append(reader: &PowermetricsReader, chunk: &[u8]) {
+        let mut data = fs::read(&reader.path).unwrap();
+        data.extend_from_slice(chunk);
+        data.push(0);
+        fs::write(&reader.path, data).unwrap();
+    }
+
+    #[test]
+    fn truncated_chunk_keeps_previous_frame() {
+        let chunk = fixture_chunk("two_cluster");
+        let mut reader = tailing("truncated", &chunk);
+        assert!(reader.parse().unwrap().is_some());
+
+        append(&reader, &chunk[..chunk.len() / 2]);
+        let reading = reader.parse().unwrap();
+        let _ = fs::remove_file(&reader.path);
+        assert!(reading.is_none());
+        assert_eq!(reader.last_chunk, chunk);
+        assert!(reader.take_failure().is_some());
+    }
+
+    #[test]
+    fn empty_snapshot_keeps_previous_frame() {
+        let chunk = fixture_chunk("two_cluster");
+        let mut reader = tailing("empty", &chunk);
+        assert!(reader.parse().unwrap().is_some());
+
+        // Mid-write snapshot: no clusters and no GPU clock.
+        let empty = br#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>gpu</key>
+	<dict>
+		<key>freq_hz</key>
+		<real>0.0</real>
+		<key>idle_ratio</key>
+		<real>1.0</real>
+	</dict>
+	<key>processor</key>
+	<dict>
+		<key>clusters</key>
+		<array/>
+	</dict>
+	<key>thermal_pressure</key>
+	<string>Nominal</string>
+	<key>timestamp</key>
+	<date>2024-05-01T12:00:02Z</date>
+</dict>
+</plist>
+"#;
+        let raw: RawSnapshot = plist::from_reader(Cursor::new(&empty[..])).unwrap();
+        assert!(convert_snapshot(raw).is_none());
+        append(&reader, empty);
+        let reading = reader.parse().unwrap();
+        let _ = fs::remove_file(&reader.path);
+        assert!(reading.is_none());
+        assert_eq!(reader.last_chunk, chunk);
+    }
+
+    #[test]
+    fn two_cluster_frequencies() {
+        let reading = fixture("two_cluster");
+        assert_eq!(reading.cpu.e_cluster_active, 50);
+        assert_eq!(reading.cpu.e_cluster_freq_mhz, 2064);
+        assert_eq!(reading.cpu.e_cluster_max_freq_mhz, 2064);
+        assert_eq!(reading.cpu.p_cluster_active, 25);
+        assert_eq!(reading.cpu.p_cluster_freq_mhz, 3228);
+        assert_eq!(reading.cpu.p_cluster_max_freq_mhz, 3504);
+        assert!(reading.gpu.available);
+        assert_eq!(reading.gpu.active_pct, 40);
+        assert_eq!(reading.gpu.freq_mhz, 1398);
+    }
+
+    #[test]
+    fn power_is_narrowed_to_f32_once() {
+        // Power math stays in f32 from here on: the millijoule energies are
+        // scaled in f64 and cast a single time, so they land exactly on the
+        // nearest f32 and downstream sums need no further casts.
+        let reading = fixture("two_cluster");
+        assert_eq!(reading.cpu.cpu_w, 2.4);
+        assert_eq!(reading.cpu.gpu_w, 1.2);
+        assert_eq!(reading.cpu.ane_w, 0.0);
+        assert_eq!(reading.cpu.package_w, 1.8);
+    }
+
+    #[test]
+    fn down_ratio_marks_parked_cores() {
+        let reading = fixture("down_ratio");
+        assert_eq!(reading.cpu.e_cluster_active, 10);
+        assert_eq!(reading.cpu.p_cluster_active, 0);
+        let down: Vec<u32> = reading
+            .cpu
+            .e_cores
+            .iter()
+            .chain(&reading.cpu.p_cores)
+            .filter(|core| core.down)
+            .map(|core| core.id)
+            .collect();
+        assert_eq!(down, [6, 7]);
+    }
+
+    #[test]
+    fn no_gpu_is_unavailable() {
+        let reading = fixture("no_gpu");
+        assert!(!reading.gpu.available);
+        assert_eq!(reading.cpu.e_cluster_active, 50);
+        assert_eq!(reading.cpu.p_cluster_freq_mhz, 3228);
+    }
+
+    #[test]
+    fn ultra_multi_cluster_lists_every_core() {
+        let reading = fixture("ultra_multi_cluster");
+        assert_eq!(reading.cpu.e_cluster_active, 50);
+        assert_eq!(reading.cpu.p_cluster_active, 75);
+        assert_eq!(reading.cpu.e_cores.len(), 8);
+        assert_eq!(reading.cpu.p_cores.len(), 16);
+    }
+
+    #[test]
+    fn missing_combined_power_sums_components() {
+        let reading = fixture("no_combined_power");
+        assert_eq!(reading.cpu.package_w, 1.8);
+        let processor = RawProcessor {
+            clusters: Vec::new(),
+            ane_energy: 0.0,
+            cpu_energy: 2400.0,
+            gpu_energy: 1200.0,
+            dram_energy: Some(400.0),
+            combined_power: 0.0,
+        };
+        assert_eq!(combined_power_mw(&processor, Some(2_000_000_000)), 2000.0);
+        assert_eq!(combined_power_mw(&processor, None), 0.0);
+    }
+
+    fn cluster(name: &str, active_pct: Option<u64>) -> ClusterData {
+        ClusterData {
+            name: name.into(),
+            active_pct,
+            freq_mhz: 1000,
+            max_freq_mhz: 0,
+        }
+    }
+
+    #[test]
+    fn idle_cluster_is_not_missing_data() {
+        // A genuinely idle cluster reports 0%, not "unknown".
+        let idle = [cluster("E-Cluster", Some(0))];
+        assert_eq!(cluster_stats(&idle, 'E'), (Some(0), Some(1000)));
+        assert_eq!(aggregate_cluster(&idle, &[], 'E'), (0, 1000));
+
+        let missing = [cluster("E-Cluster", None)];
+        assert_eq!(cluster_stats(&missing, 'E'), (None, Some(1000)));
+
+        // Split clusters average only those that reported; an idle one
+        // still counts and is not dropped with the missing one.
+        let split = [cluster("E0-Cluster", Some(0)), cluster("E1-Cluster", None)];
+        assert_eq!(cluster_stats(&split, 'E'), (Some(0), Some(1000)));
+        let unknown = [cluster("E0-Cluster", None), cluster("E1-Cluster", None)];
+        assert_eq!(cluster_stats(&unknown, 'E'), (None, Some(1000)));
+    }
+
+    #[test]
+    fn core_average_takes_precedence_over_cluster() {
+        let cores = [
+            CoreMetrics {
+                id: 0,
+                active_pct: 0,
+                freq_mhz: 600,
+                down: false,
+            },
+            CoreMetrics {
+                id: 1,
+                active_pct: 0,
+                freq_mhz: 600,
+                down: false,
+            },
+        ];
+        // Idle cores keep the cluster at 0% even when the cluster's own
+        // residency says otherwise.
+        let busy = [cluster("E-Cluster", Some(40))];
+        assert_eq!(aggregate_cluster(&busy, &cores, 'E'), (0, 1000));
+        // With no cores and no residency, usage falls back to 0.
+        let missing = [cluster("E-Cluster", None)];
+        assert_eq!(aggregate_cluster(&missing, &[], 'E'), (0, 1000));
+    }
+
+    #[test]
+    fn display_freq_infers_the_unit() {
+        assert_eq!(display_freq(3.2e9), 3200);
+        assert_eq!(display_freq(3_200_000.0), 3200);
+        assert_eq!(display_freq(3200.0), 3200);
+        // An idle GPU clock in Hz sits below the range; the nearest
+        // interpretation (MHz from Hz) still wins over kHz or MHz.
+        assert_eq!(display_freq(389_000_000.0), 389);
+        assert_eq!(display_freq(0.0), 0);
+    }
+
+    #[test]
+    fn rolling_window_rounds_to_whole_samples() {
+        assert_eq!(rolling_window(30, 1000), 30);
+        // 30 s / 7 s = 4.3 samples.
+        assert_eq!(rolling_window(30, 7000), 4);
+        // Half a sample still gets the minimum window.
+        assert_eq!(rolling_window(5, 10_000), MIN_ROLLING_WINDOW);
+    }
+
+    #[test]
+    fn zero_window_keeps_latest_sample() {
+        let mut average = RollingAverage::new(0);
+        average.push(1.0);
+        average.push(3.0);
+        assert_eq!(average.average(), 3.0);
+    }
 }
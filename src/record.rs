@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// `--record`: the full-resolution power time series as whitespace-separated
+/// columns with `#` comment headers, readable as-is by gnuplot and
+/// `numpy.loadtxt`. Every row is flushed so the file is usable mid-run.
+pub struct PowerRecorder {
+    out: BufWriter<File>,
+}
+
+impl PowerRecorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        writeln!(out, "# asitop power record")?;
+        writeln!(
+            out,
+            "# unix_time: seconds since 1970-01-01 UTC; *_w: average watts over the sample"
+        )?;
+        writeln!(out, "# unix_time cpu_w gpu_w package_w")?;
+        out.flush()?;
+        Ok(Self { out })
+    }
+
+    pub fn write(&mut self, at: SystemTime, cpu_w: f32, gpu_w: f32, package_w: f32) -> Result<()> {
+        let unix = at
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs_f64())
+            .unwrap_or(0.0);
+        writeln!(self.out, "{unix:.3} {cpu_w:.3} {gpu_w:.3} {package_w:.3}")?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::iokit::{
+    IOConnectCallStructMethod, IOObjectRelease, IOServiceClose, IOServiceGetMatchingService,
+    IOServiceMatching, IOServiceOpen, io_connect_t,
+};
+use anyhow::{Result, bail};
+use libc::{KERN_SUCCESS, c_void};
+use std::mem;
+
+/// `AppleSMC` user client selector that carries every SMC command.
+const SMC_HANDLE_EVENT: u32 = 2;
+const CMD_READ_BYTES: u8 = 5;
+const CMD_READ_INDEX: u8 = 8;
+const CMD_READ_KEYINFO: u8 = 9;
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct KeyDataVersion {
+    major: u8,
+    minor: u8,
+    build: u8,
+    reserved: u8,
+    release: u16,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct PowerLimitData {
+    version: u16,
+    length: u16,
+    cpu_limit: u32,
+    gpu_limit: u32,
+    mem_limit: u32,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct KeyInfo {
+    data_size: u32,
+    data_type: u32,
+    data_attributes: u8,
+}
+
+/// The 80-byte in/out structure of `SMC_HANDLE_EVENT`.
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct KeyData {
+    key: u32,
+    version: KeyDataVersion,
+    power_limit: PowerLimitData,
+    info: KeyInfo,
+    result: u8,
+    status: u8,
+    command: u8,
+    index: u32,
+    bytes: [u8; 32],
+}
+
+const _: () = assert!(mem::size_of::<KeyData>() == 80);
+
+/// One SMC key with its raw payload.
+pub struct Sensor {
+    pub key: String,
+    pub data_type: String,
+    pub bytes: Vec<u8>,
+}
+
+impl Sensor {
+    /// Human-readable value for the common SMC types; anything else is
+    /// shown as hex so it can still be reported and mapped later.
+    pub fn decode(&self) -> String {
+        let b = &self.bytes;
+        match (self.data_type.as_str(), b.len()) {
+            ("flt ", 4) => format!("{:.2}", f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            ("ui8 " | "flag", 1) => b[0].to_string(),
+            ("ui16", 2) => u16::from_be_bytes([b[0], b[1]]).to_string(),
+            ("ui32", 4) => u32::from_be_bytes([b[0], b[1], b[2], b[3]]).to_string(),
+            ("si8 ", 1) => (b[0] as i8).to_string(),
+            ("si16", 2) => i16::from_be_bytes([b[0], b[1]]).to_string(),
+            ("sp78", 2) => format!("{:.2}", i16::from_be_bytes([b[0], b[1]]) as f32 / 256.0),
+            ("fpe2", 2) => format!("{:.2}", u16::from_be_bytes([b[0], b[1]]) as f32 / 4.0),
+            _ => b.iter().map(|byte| format!("{byte:02x}")).collect(),
+        }
+    }
+}
+
+/// An open connection to the `AppleSMC` user client.
+pub struct Smc {
+    connection: io_connect_t,
+}
+
+impl Smc {
+    pub fn open() -> Result<Self> {
+        unsafe {
+            let matching = IOServiceMatching(c"AppleSMC".as_ptr());
+            if matching.is_null() {
+                bail!("failed to build the AppleSMC matching dictionary");
+            }
+            let service = IOServiceGetMatchingService(0, matching);
+            if service == 0 {
+                bail!("AppleSMC service not found");
+            }
+            let mut connection: io_connect_t = 0;
+            #[allow(deprecated)]
+            let task = libc::mach_task_self();
+            let result = IOServiceOpen(service, task, 0, &mut connection);
+            IOObjectRelease(service);
+            if result != KERN_SUCCESS {
+                bail!("failed to open AppleSMC (kern_return_t {result})");
+            }
+            Ok(Self { connection })
+        }
+    }
+
+    fn call(&self, input: &KeyData) -> Option<KeyData> {
+        let mut output = KeyData::default();
+        let mut output_size = mem::size_of::<KeyData>();
+        let result = unsafe {
+            IOConnectCallStructMethod(
+                self.connection,
+                SMC_HANDLE_EVENT,
+                input as *const KeyData as *const c_void,
+                mem::size_of::<KeyData>(),
+                &mut output as *mut KeyData as *mut c_void,
+                &mut output_size,
+            )
+        };
+        (result == KERN_SUCCESS && output.result == 0).then_some(output)
+    }
+
+    fn key_info(&self, key: u32) -> Option<KeyInfo> {
+        let input = KeyData {
+            key,
+            command: CMD_READ_KEYINFO,
+            ..Default::default()
+        };
+        self.call(&input).map(|output| output.info)
+    }
+
+    fn key_at(&self, index: u32) -> Option<u32> {
+        let input = KeyData {
+            command: CMD_READ_INDEX,
+            index,
+            ..Default::default()
+        };
+        self.call(&input).map(|output| output.key)
+    }
+
+    /// Read a key by its four-character code.
+    pub fn read(&self, key: u32) -> Option<Sensor> {
+        let info = self.key_info(key)?;
+        let input = KeyData {
+            key,
+            info,
+            command: CMD_READ_BYTES,
+            ..Default::default()
+        };
+        let output = self.call(&input)?;
+        let len = (info.data_size as usize).min(output.bytes.len());
+        Some(Sensor {
+            key: fourcc_to_string(key),
+            data_type: fourcc_to_string(info.data_type),
+            bytes: output.bytes[..len].to_vec(),
+        })
+    }
+
+    /// Every key the SMC exposes, in index order. Keys that fail to read
+    /// are skipped.
+    pub fn sensors(&self) -> Vec<Sensor> {
+        let count = self
+            .read(fourcc(b"#KEY"))
+            .and_then(|sensor| sensor.bytes.try_into().ok())
+            .map(u32::from_be_bytes)
+            .unwrap_or(0);
+        (0..count)
+            .filter_map(|index| self.key_at(index))
+            .filter_map(|key| self.read(key))
+            .collect()
+    }
+}
+
+impl Drop for Smc {
+    fn drop(&mut self) {
+        unsafe {
+            IOServiceClose(self.connection);
+        }
+    }
+}
+
+/// `--list-sensors`: print every SMC key as a table.
+pub fn print_sensors() -> Result<()> {
+    let sensors = Smc::open()?.sensors();
+    if sensors.is_empty() {
+        bail!("the SMC reported no readable keys");
+    }
+    println!("{:<6} {:<6} {:>4}  VALUE", "KEY", "TYPE", "SIZE");
+    for sensor in &sensors {
+        println!(
+            "{:<6} {:<6} {:>4}  {}",
+            sensor.key,
+            sensor.data_type.trim_end(),
+            sensor.bytes.len(),
+            sensor.decode()
+        );
+    }
+    println!("\n{} keys", sensors.len());
+    Ok(())
+}
+
+fn fourcc(code: &[u8; 4]) -> u32 {
+    u32::from_be_bytes(*code)
+}
+
+fn fourcc_to_string(code: u32) -> String {
+    String::from_utf8_lossy(&code.to_be_bytes()).into_owned()
+}
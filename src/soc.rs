@@ -1,6 +1,17 @@
-use std::process::Command;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Read,
+    mem,
+    process::{Command, Stdio},
+    ptr, thread,
+    time::{Duration, Instant},
+};
 
-#[derive(Debug, Clone)]
+/// `system_profiler` can stall for a long time on managed Macs; past this the
+/// GPU core count is reported as unknown rather than blocking startup.
+const SYSTEM_PROFILER_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SocInfo {
     pub name: String,
     pub e_core_count: u32,
@@ -39,18 +50,52 @@ impl SocInfo {
 /// Lookup CPU, GPU and ANE TDP based on chip name
 /// Returns (cpu_max_power, gpu_max_power, ane_max_power) in watts
 fn lookup_caps(name: &str) -> (f32, f32, f32) {
+    let ane_max_power = lookup_ane_power(name);
     if name.ends_with("Ultra") {
-        return (150.0, 150.0, 16.0);
+        return (150.0, 150.0, ane_max_power);
     } else if name.ends_with("Max") {
-        return (100.0, 100.0, 16.0);
+        return (100.0, 100.0, ane_max_power);
     } else if name.ends_with("Pro") {
-        return (45.0, 45.0, 8.0);
+        return (45.0, 45.0, ane_max_power);
     } else {
-        return (25.0, 25.0, 8.0);
+        return (25.0, 25.0, ane_max_power);
     }
 
 }
 
+/// Peak ANE draw for the chip. Pro and Max parts carry the same single
+/// 16-core Neural Engine as the base chip of their generation; Ultra fuses
+/// two dies and so has two of them.
+fn lookup_ane_power(name: &str) -> f32 {
+    let per_engine = match name.split_whitespace().find(|word| word.starts_with('M')) {
+        Some("M1") => 8.0,
+        Some("M2") | Some("M3") => 9.0,
+        Some("M4") => 10.0,
+        _ => 8.0,
+    };
+    let engines = if name.ends_with("Ultra") { 2.0 } else { 1.0 };
+    per_engine * engines
+}
+
+/// Whether this process is an x86_64 binary running under Rosetta.
+/// `sysctl.proc_translated` describes the calling process, so it has to be
+/// queried in-process rather than through the (native) sysctl tool.
+pub fn is_translated() -> bool {
+    let mut value: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>();
+    let result = unsafe {
+        libc::sysctlbyname(
+            c"sysctl.proc_translated".as_ptr(),
+            &mut value as *mut _ as *mut libc::c_void,
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    // ENOENT means the key does not exist, i.e. not running on Rosetta.
+    result == 0 && value == 1
+}
+
 fn read_sysctl(key: &str) -> Option<String> {
     let output = Command::new("/usr/sbin/sysctl")
         .args(["-n", key])
@@ -64,14 +109,39 @@ fn read_sysctl(key: &str) -> Option<String> {
 }
 
 fn read_gpu_core_count() -> Option<u32> {
-    let output = Command::new("/usr/sbin/system_profiler")
+    let mut child = Command::new("/usr/sbin/system_profiler")
         .args(["-detailLevel", "basic", "SPDisplaysDataType"])
-        .output()
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
         .ok()?;
-    if !output.status.success() {
+    // Drain stdout on a thread so a full pipe cannot stall the child.
+    let mut pipe = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut stdout = Vec::new();
+        pipe.read_to_end(&mut stdout).map(|_| stdout)
+    });
+    let deadline = Instant::now() + SYSTEM_PROFILER_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            _ => {
+                child.kill().ok();
+                child.wait().ok();
+                eprintln!(
+                    "note: system_profiler did not answer within {}s; GPU core count unknown",
+                    SYSTEM_PROFILER_TIMEOUT.as_secs()
+                );
+                return None;
+            }
+        }
+    };
+    let stdout = reader.join().ok()?.ok()?;
+    if !status.success() {
         return None;
     }
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = String::from_utf8_lossy(&stdout);
     for line in stdout.lines() {
         if let Some(rest) = line.trim().strip_prefix("Total Number of Cores: ") {
             if let Ok(value) = rest.trim().parse() {
@@ -0,0 +1,221 @@
+use crate::config::Lang;
+
+/// User-facing UI labels, selected with `--lang`.
+pub struct Strings {
+    pub no_cores: &'static str,
+    pub core_off: &'static str,
+    pub more: &'static str,
+    pub e_cores: &'static str,
+    pub p_cores: &'static str,
+    pub e_cpu_usage: &'static str,
+    pub p_cpu_usage: &'static str,
+    pub gpu_usage: &'static str,
+    pub gpu_memory: &'static str,
+    pub ane_usage: &'static str,
+    pub ram_usage: &'static str,
+    pub swap: &'static str,
+    pub swap_inactive: &'static str,
+    pub memory: &'static str,
+    pub io: &'static str,
+    pub network_io: &'static str,
+    pub disk_io: &'static str,
+    pub net_in: &'static str,
+    pub net_out: &'static str,
+    pub packets: &'static str,
+    pub disk_read: &'static str,
+    pub disk_write: &'static str,
+    pub process: &'static str,
+    pub process_exited: &'static str,
+    pub waiting_for_process: &'static str,
+    pub waiting_for_tasks: &'static str,
+    pub waiting_for_powermetrics: &'static str,
+    pub energy_impact: &'static str,
+    pub system_power: &'static str,
+    pub energy: &'static str,
+    pub power_budget: &'static str,
+    pub paused: &'static str,
+    pub terminal_too_small: &'static str,
+    pub top_processes: &'static str,
+    pub column_name: &'static str,
+    pub column_cpu_time: &'static str,
+    pub column_energy: &'static str,
+    pub diagnostics: &'static str,
+    pub powermetrics_file: &'static str,
+    pub bytes: &'static str,
+    pub last_parse: &'static str,
+    pub plist_chunks: &'static str,
+    pub dropped_samples: &'static str,
+    pub timecode: &'static str,
+    pub parse_error: &'static str,
+    pub ane_from_residency: &'static str,
+    pub ane_from_estimate: &'static str,
+    pub uptime: &'static str,
+    pub sample: &'static str,
+    pub every: &'static str,
+    pub auto: &'static str,
+    pub asked: &'static str,
+    pub dropped: &'static str,
+    pub avg: &'static str,
+    pub p95: &'static str,
+    pub peak: &'static str,
+    pub power: &'static str,
+    pub power_history: &'static str,
+    pub last: &'static str,
+    pub thermal: &'static str,
+    pub throttling: &'static str,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub not_available: &'static str,
+    pub battery: &'static str,
+    pub charging: &'static str,
+    pub on_ac: &'static str,
+}
+
+pub const EN: Strings = Strings {
+    no_cores: "No cores detected",
+    core_off: "off",
+    more: "more",
+    e_cores: "E-Cores",
+    p_cores: "P-Cores",
+    e_cpu_usage: "E-CPU Usage",
+    p_cpu_usage: "P-CPU Usage",
+    gpu_usage: "GPU Usage",
+    gpu_memory: "GPU mem",
+    ane_usage: "ANE Usage",
+    ram_usage: "RAM Usage",
+    swap: "swap",
+    swap_inactive: "swap inactive",
+    memory: "Memory",
+    io: "I/O",
+    network_io: "Network I/O",
+    disk_io: "Disk I/O",
+    net_in: "In",
+    net_out: "Out",
+    packets: "Pkts",
+    disk_read: "Read",
+    disk_write: "Write",
+    process: "Process",
+    process_exited: "process exited",
+    waiting_for_process: "waiting for process...",
+    waiting_for_tasks: "waiting for tasks sample...",
+    waiting_for_powermetrics: "Waiting for powermetrics…",
+    energy_impact: "Energy Impact",
+    system_power: "Approx system power",
+    energy: "Energy",
+    power_budget: "Budget",
+    paused: "paused",
+    terminal_too_small: "Terminal too small",
+    top_processes: "Top Processes by Energy Impact",
+    column_name: "Name",
+    column_cpu_time: "CPU ms/s",
+    column_energy: "Energy",
+    diagnostics: "Diagnostics",
+    powermetrics_file: "powermetrics file",
+    bytes: "bytes",
+    last_parse: "last parse",
+    plist_chunks: "plist chunks",
+    dropped_samples: "dropped samples",
+    timecode: "timecode",
+    parse_error: "parse error",
+    ane_from_residency: "powermetrics residency",
+    ane_from_estimate: "power / max power estimate",
+    uptime: "up",
+    sample: "sample",
+    every: "every",
+    auto: "auto",
+    asked: "asked",
+    dropped: "dropped",
+    avg: "avg",
+    p95: "p95",
+    peak: "peak",
+    power: "Power",
+    power_history: "Power history",
+    last: "last",
+    thermal: "thermal",
+    throttling: "THROTTLING",
+    yes: "yes",
+    no: "no",
+    not_available: "n/a",
+    battery: "Battery",
+    charging: "charging",
+    on_ac: "AC",
+};
+
+pub const ZH: Strings = Strings {
+    no_cores: "未检测到核心",
+    core_off: "停用",
+    more: "更多",
+    e_cores: "能效核心",
+    p_cores: "性能核心",
+    e_cpu_usage: "E-CPU 占用",
+    p_cpu_usage: "P-CPU 占用",
+    gpu_usage: "GPU 占用",
+    gpu_memory: "GPU 内存",
+    ane_usage: "ANE 占用",
+    ram_usage: "内存占用",
+    swap: "交换区",
+    swap_inactive: "交换区未启用",
+    memory: "内存",
+    io: "I/O",
+    network_io: "网络 I/O",
+    disk_io: "磁盘 I/O",
+    net_in: "接收",
+    net_out: "发送",
+    packets: "包数",
+    disk_read: "读取",
+    disk_write: "写入",
+    process: "进程",
+    process_exited: "进程已退出",
+    waiting_for_process: "等待进程出现...",
+    waiting_for_tasks: "等待 tasks 采样...",
+    waiting_for_powermetrics: "等待 powermetrics 数据…",
+    energy_impact: "能耗影响",
+    system_power: "整机功耗估算",
+    energy: "累计能耗",
+    power_budget: "功耗预算",
+    paused: "已暂停",
+    terminal_too_small: "终端窗口过小",
+    top_processes: "能耗最高的进程",
+    column_name: "名称",
+    column_cpu_time: "CPU 时间",
+    column_energy: "能耗",
+    diagnostics: "诊断信息",
+    powermetrics_file: "powermetrics 文件",
+    bytes: "字节",
+    last_parse: "最近解析",
+    plist_chunks: "plist 分块",
+    dropped_samples: "丢弃样本",
+    timecode: "timecode",
+    parse_error: "解析错误",
+    ane_from_residency: "powermetrics 驻留率",
+    ane_from_estimate: "功耗 / 最大功耗估算",
+    uptime: "运行",
+    sample: "采样",
+    every: "间隔",
+    auto: "自动",
+    asked: "请求",
+    dropped: "丢弃",
+    avg: "均值",
+    p95: "P95",
+    peak: "峰值",
+    power: "功耗",
+    power_history: "功耗历史",
+    last: "最近",
+    thermal: "热状态",
+    throttling: "降频中",
+    yes: "是",
+    no: "否",
+    not_available: "不可用",
+    battery: "电池",
+    charging: "充电中",
+    on_ac: "外接电源",
+};
+
+impl Strings {
+    pub fn for_lang(lang: Lang) -> &'static Strings {
+        match lang {
+            Lang::En => &EN,
+            Lang::Zh => &ZH,
+        }
+    }
+}
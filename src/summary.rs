@@ -0,0 +1,134 @@
+use crate::{powermetrics::RollingAverage, thermal::ThermalLevel};
+use std::fmt::Write;
+
+/// Aggregates power readings over a fixed number of samples for the
+/// non-interactive `--output-once` report.
+pub struct Summary {
+    samples: u64,
+    cpu: PowerSeries,
+    gpu: PowerSeries,
+    package: PowerSeries,
+    max_thermal: Option<ThermalLevel>,
+}
+
+/// Most samples kept for the p95, about 12 MB across the three series.
+const MAX_PERCENTILE_WINDOW: usize = 1 << 20;
+
+/// The mean and peak cover every recorded sample; p95 only the newest
+/// `window`, so memory stays bounded when more samples arrive than expected.
+struct PowerSeries {
+    recent: RollingAverage,
+    sum: f64,
+    count: u64,
+    peak: f32,
+}
+
+impl PowerSeries {
+    fn new(window: usize) -> Self {
+        Self {
+            recent: RollingAverage::new(window),
+            sum: 0.0,
+            count: 0,
+            peak: 0.0,
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        self.recent.push(value);
+        self.sum += value as f64;
+        self.count += 1;
+        self.peak = self.peak.max(value);
+    }
+
+    fn mean(&self) -> f32 {
+        if self.count == 0 {
+            0.0
+        } else {
+            (self.sum / self.count as f64) as f32
+        }
+    }
+}
+
+impl Summary {
+    /// `samples` sizes the p95 window, capped at `MAX_PERCENTILE_WINDOW`.
+    pub fn new(samples: usize) -> Self {
+        let window = samples.clamp(1, MAX_PERCENTILE_WINDOW);
+        Self {
+            samples: 0,
+            cpu: PowerSeries::new(window),
+            gpu: PowerSeries::new(window),
+            package: PowerSeries::new(window),
+            max_thermal: None,
+        }
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    pub fn record(
+        &mut self,
+        cpu_w: f32,
+        gpu_w: f32,
+        package_w: f32,
+        thermal: Option<ThermalLevel>,
+    ) {
+        self.samples += 1;
+        self.cpu.push(cpu_w);
+        self.gpu.push(gpu_w);
+        self.package.push(package_w);
+        if let Some(level) = thermal {
+            let worse = self
+                .max_thermal
+                .map(|current| level.severity() > current.severity())
+                .unwrap_or(true);
+            if worse {
+                self.max_thermal = Some(level);
+            }
+        }
+    }
+
+    /// Plain-text report with one aligned `key value` row per metric.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "{:<20} {}", "samples", self.samples).ok();
+        for (label, series) in [
+            ("cpu_power", &self.cpu),
+            ("gpu_power", &self.gpu),
+            ("package_power", &self.package),
+        ] {
+            writeln!(
+                out,
+                "{:<20} mean {:>8.2} W  p95 {:>8.2} W  peak {:>8.2} W",
+                label,
+                series.mean(),
+                series.recent.percentile(95.0),
+                series.peak
+            )
+            .ok();
+        }
+        let thermal = self
+            .max_thermal
+            .map(|level| level.to_string())
+            .unwrap_or_else(|| "n/a".into());
+        writeln!(out, "{:<20} {}", "max_thermal_level", thermal).ok();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_covers_samples_past_the_window() {
+        let mut summary = Summary::new(2);
+        for watts in [1.0, 2.0, 3.0, 6.0] {
+            summary.record(watts, 0.0, watts, None);
+        }
+        assert_eq!(summary.samples(), 4);
+        assert_eq!(summary.cpu.mean(), 3.0);
+        assert_eq!(summary.cpu.peak, 6.0);
+        assert!(summary.render().contains("mean     3.00 W"));
+    }
+}
@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// `kIOPMThermalLevelUnknown`: documented, but says nothing about the level.
+const LEVEL_UNKNOWN: u32 = 255;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ThermalLevel {
     Normal,
     Danger,
@@ -9,8 +13,26 @@ pub enum ThermalLevel {
 }
 
 impl ThermalLevel {
+    /// Unmapped codes, including `kIOPMThermalLevelUnknown`, only mean the
+    /// level is unknown, so they never count as throttling.
     pub fn is_throttled(self) -> bool {
-        !matches!(self, ThermalLevel::Normal)
+        matches!(self, ThermalLevel::Danger | ThermalLevel::Crisis)
+    }
+
+    /// A code outside the documented table, worth reporting so the mapping
+    /// can be extended.
+    pub fn is_unmapped(self) -> bool {
+        matches!(self, ThermalLevel::Unknown(raw) if raw != LEVEL_UNKNOWN)
+    }
+
+    /// Ordering used to pick the worst level seen; unmapped values rank with
+    /// nominal since they say nothing about throttling.
+    pub fn severity(self) -> u8 {
+        match self {
+            ThermalLevel::Normal | ThermalLevel::Unknown(_) => 0,
+            ThermalLevel::Danger => 2,
+            ThermalLevel::Crisis => 3,
+        }
     }
 
     pub fn label(self) -> &'static str {
@@ -32,6 +54,21 @@ impl fmt::Display for ThermalLevel {
     }
 }
 
+/// `IOPMGetThermalWarningLevel` codes. Only the documented constants are
+/// mapped; anything else stays `Unknown(raw)`, and codes missing from the
+/// table are logged at startup.
+///
+/// | Raw       | Constant                          | Level   |
+/// | --------- | --------------------------------- | ------- |
+/// | 0         | `kIOPMThermalLevelNormal`         | Normal  |
+/// | 5         | `kIOPMThermalLevelDanger`         | Danger  |
+/// | 100       | `kIOPMThermalLevelWarning`        | Danger  |
+/// | 10        | `kIOPMThermalLevelCritical`       | Crisis  |
+/// | 110       | `kIOPMThermalLevelTrap`           | Crisis  |
+/// | 255, rest | `kIOPMThermalLevelUnknown`, other | Unknown |
+///
+/// Despite its name, `kIOPMThermalLevelWarning` is reported while the
+/// system is already limiting performance, so it counts as throttled.
 impl From<u32> for ThermalLevel {
     fn from(value: u32) -> Self {
         match value {
@@ -59,3 +96,34 @@ pub fn read_warning_level() -> Option<ThermalLevel> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_documented_warning_levels() {
+        let cases = [
+            (0, ThermalLevel::Normal, false),
+            (5, ThermalLevel::Danger, true),
+            (100, ThermalLevel::Danger, true),
+            (10, ThermalLevel::Crisis, true),
+            (110, ThermalLevel::Crisis, true),
+            (3, ThermalLevel::Unknown(3), false),
+            (7, ThermalLevel::Unknown(7), false),
+            (255, ThermalLevel::Unknown(255), false),
+        ];
+        for (raw, level, throttled) in cases {
+            assert_eq!(ThermalLevel::from(raw), level, "raw {raw}");
+            assert_eq!(level.is_throttled(), throttled, "raw {raw}");
+            assert_eq!(level.severity() > 0, throttled, "raw {raw}");
+        }
+    }
+
+    #[test]
+    fn only_undocumented_codes_are_unmapped() {
+        assert!(ThermalLevel::from(7).is_unmapped());
+        assert!(!ThermalLevel::from(255).is_unmapped());
+        assert!(!ThermalLevel::from(5).is_unmapped());
+    }
+}
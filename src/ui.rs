@@ -1,8 +1,15 @@
 use crate::{
+    battery::BatteryStats,
+    config::{
+        BarChars, DEFAULT_LOAD_THRESHOLDS, FreqUnit, NetUnit, Panel, PercentBasis, ThemeFile,
+        ThemeName,
+    },
     io_stats::IoStats,
     memory::MemoryStats,
-    powermetrics::{CoreMetrics, CpuMetrics, GpuMetrics},
+    powermetrics::{CoreMetrics, CpuMetrics, GpuMetrics, ReaderStats, TaskMetrics},
     soc::SocInfo,
+    strings::Strings,
+    thermal::ThermalLevel,
 };
 use ratatui::{
     Frame,
@@ -10,13 +17,187 @@ use ratatui::{
     prelude::*,
     style::Modifier,
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, Paragraph, RenderDirection, Sparkline, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, RenderDirection, Sparkline, Wrap},
 };
+use serde::Serialize;
+use std::{cell::Cell, time::Duration};
+
+/// Sparkline levels for `--ascii`.
+const ASCII_BARS: symbols::bar::Set = symbols::bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "=",
+    five_eighths: "=",
+    half: "-",
+    three_eighths: "-",
+    one_quarter: "_",
+    one_eighth: "_",
+    empty: " ",
+};
+
+/// Below this size the fixed-height panels cannot be laid out at all.
+const MIN_WIDTH: u16 = 20;
+const MIN_HEIGHT: u16 = 8;
 
-const CORE_MAX_COLUMNS: usize = 4;
 const CORE_FIXED_WIDTH: usize = 18;
 const CORE_MIN_BAR_WIDTH: usize = 6;
 const CORE_MIN_ENTRY_WIDTH: usize = CORE_FIXED_WIDTH + CORE_MIN_BAR_WIDTH;
+/// Below this inner width the processor panel drops the bars and shows
+/// percentages only.
+const PROCESSOR_COMPACT_WIDTH: u16 = 40;
+/// Width of one "E01 100%" entry in the compact core grid.
+const CORE_COMPACT_ENTRY_WIDTH: usize = 9;
+
+/// Coordinated palette used by every styled element of the UI.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// Borders, titles and gauge labels.
+    pub accent: Color,
+    /// Plain text such as the power summary lines.
+    pub text: Color,
+    /// Empty bar segments and placeholder messages.
+    pub muted: Color,
+    /// Row labels in the I/O and process panels.
+    pub label: Color,
+    /// Per-core frequency readouts.
+    pub frequency: Color,
+    pub warning: Color,
+    pub critical: Color,
+    /// Load gradient from idle to saturated, one color per band between
+    /// `load_thresholds`.
+    pub gradient: [Color; 5],
+    /// Load percentages where `gradient` steps to its next color.
+    pub load_thresholds: [u64; 4],
+}
+
+impl Theme {
+    pub fn preset(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self {
+                accent: Color::Green,
+                text: Color::Reset,
+                muted: Color::DarkGray,
+                label: Color::Gray,
+                frequency: Color::Yellow,
+                warning: Color::Yellow,
+                critical: Color::Red,
+                gradient: [
+                    Color::Cyan,
+                    Color::LightGreen,
+                    Color::Yellow,
+                    Color::LightRed,
+                    Color::Red,
+                ],
+                load_thresholds: DEFAULT_LOAD_THRESHOLDS,
+            },
+            ThemeName::Light => Self {
+                accent: Color::Blue,
+                text: Color::Black,
+                muted: Color::Gray,
+                label: Color::DarkGray,
+                frequency: Color::Magenta,
+                warning: Color::Magenta,
+                critical: Color::Red,
+                gradient: [
+                    Color::Blue,
+                    Color::Green,
+                    Color::Magenta,
+                    Color::LightRed,
+                    Color::Red,
+                ],
+                load_thresholds: DEFAULT_LOAD_THRESHOLDS,
+            },
+            ThemeName::Solarized => Self {
+                accent: Color::Rgb(42, 161, 152),
+                text: Color::Rgb(131, 148, 150),
+                muted: Color::Rgb(88, 110, 117),
+                label: Color::Rgb(147, 161, 161),
+                frequency: Color::Rgb(181, 137, 0),
+                warning: Color::Rgb(203, 75, 22),
+                critical: Color::Rgb(220, 50, 47),
+                gradient: [
+                    Color::Rgb(38, 139, 210),
+                    Color::Rgb(133, 153, 0),
+                    Color::Rgb(181, 137, 0),
+                    Color::Rgb(203, 75, 22),
+                    Color::Rgb(220, 50, 47),
+                ],
+                load_thresholds: DEFAULT_LOAD_THRESHOLDS,
+            },
+            ThemeName::Mono => Self {
+                accent: Color::Reset,
+                text: Color::Reset,
+                muted: Color::DarkGray,
+                label: Color::Reset,
+                frequency: Color::Reset,
+                warning: Color::Reset,
+                critical: Color::Reset,
+                gradient: [
+                    Color::DarkGray,
+                    Color::Gray,
+                    Color::Gray,
+                    Color::White,
+                    Color::White,
+                ],
+                load_thresholds: DEFAULT_LOAD_THRESHOLDS,
+            },
+        }
+    }
+
+    /// Every element in the terminal's default colors, for `--no-color`.
+    pub fn monochrome() -> Self {
+        Self {
+            accent: Color::Reset,
+            text: Color::Reset,
+            muted: Color::Reset,
+            label: Color::Reset,
+            frequency: Color::Reset,
+            warning: Color::Reset,
+            critical: Color::Reset,
+            gradient: [Color::Reset; 5],
+            load_thresholds: DEFAULT_LOAD_THRESHOLDS,
+        }
+    }
+
+    /// Apply the colors set in a `--theme-file`.
+    pub fn with_file(mut self, file: &ThemeFile) -> Self {
+        let slots = [
+            (&mut self.accent, file.accent),
+            (&mut self.text, file.text),
+            (&mut self.muted, file.muted),
+            (&mut self.label, file.label),
+            (&mut self.frequency, file.frequency),
+            (&mut self.warning, file.warning),
+            (&mut self.critical, file.critical),
+        ];
+        for (slot, color) in slots {
+            if let Some(color) = color {
+                *slot = color.0;
+            }
+        }
+        if let Some(gradient) = file.gradient {
+            self.gradient = gradient.map(|color| color.0);
+        }
+        self
+    }
+
+    pub fn load_color(&self, percent: u64) -> Color {
+        let band = self
+            .load_thresholds
+            .iter()
+            .take_while(|threshold| percent >= **threshold)
+            .count();
+        self.gradient[band]
+    }
+}
+
+/// E-cluster, P-cluster and GPU clocks in MHz, one slot per sample interval
+/// like `UiSnapshot::power_history`.
+pub struct FreqHistory {
+    pub e_cluster: Vec<Option<u64>>,
+    pub p_cluster: Vec<Option<u64>>,
+    pub gpu: Vec<Option<u64>>,
+}
 
 pub struct UiSnapshot<'a> {
     pub soc: &'a SocInfo,
@@ -24,9 +205,65 @@ pub struct UiSnapshot<'a> {
     pub gpu: &'a GpuMetrics,
     pub memory: &'a MemoryStats,
     pub io: IoStats,
+    /// Which halves of the I/O panel are sampled.
+    pub net_io: bool,
+    pub disk_io: bool,
     pub thermal_throttle: bool,
-    pub color: Color,
+    /// Full IOKit thermal warning level; `None` when it couldn't be read.
+    pub thermal_level: Option<ThermalLevel>,
+    pub battery: Option<BatteryStats>,
+    /// Unified memory mapped by the GPU, when IOKit reports it.
+    pub gpu_memory_gb: Option<f32>,
+    /// Package power is above `--alert-watts`.
+    pub power_alert: bool,
+    /// Short-lived status line, e.g. after saving a snapshot.
+    pub status: Option<&'a str>,
+    /// False until the first powermetrics reading arrives.
+    pub initialized: bool,
+    pub theme: Theme,
+    /// Colors are off; states that are otherwise told apart by color use
+    /// glyphs instead.
+    pub monochrome: bool,
+    pub strings: &'static Strings,
+    /// Time since asitop started.
+    pub uptime: Duration,
+    /// Local wall-clock time of the latest sample.
+    pub sample_clock: Option<String>,
+    /// Stale or duplicate readings discarded so far.
+    pub dropped_samples: u64,
+    /// Cadence powermetrics actually delivers, from its sample windows.
+    pub sample_interval: Duration,
+    /// Interval powermetrics was asked for: `--interval`, or the one
+    /// `--auto-interval` settled on.
+    pub requested_interval: Duration,
+    /// Whether `--auto-interval` may change the interval.
+    pub auto_interval: bool,
+    /// Verbose diagnostics overlay, when enabled.
+    pub diagnostics: Option<Diagnostics>,
     pub show_cores: bool,
+    /// Main panels in display order.
+    pub panels: &'a [Panel],
+    /// Panel maximized with `z`, drawn alone instead of the grid.
+    pub focus: Option<Panel>,
+    /// Rows scrolled past in the per-core panels.
+    pub core_scroll: usize,
+    /// Furthest `core_scroll` the per-core panels can use at this size, set
+    /// by the renderer so the caller can clamp its offset; `None` when no
+    /// core grid was drawn.
+    pub core_scroll_limit: Cell<Option<usize>>,
+    pub freq_unit: FreqUnit,
+    pub net_unit: NetUnit,
+    pub percent_basis: PercentBasis,
+    /// Usage bar characters.
+    pub bar_chars: BarChars,
+    /// Avoid non-ASCII glyphs everywhere (`--ascii`).
+    pub ascii: bool,
+    /// Show the load color legend under the core grid.
+    pub legend: bool,
+    /// Upper bound on cores per row in the core grid (`--core-columns`).
+    pub core_columns: usize,
+    /// Color gauge fills by load instead of the flat accent color.
+    pub load_color: bool,
     pub ane_percent: u64,
     pub ane_power_w: f32,
     pub ram_has_swap: bool,
@@ -34,51 +271,354 @@ pub struct UiSnapshot<'a> {
     pub swap_total_gb: f32,
     pub cpu_power: PowerSnapshot,
     pub gpu_power: PowerSnapshot,
+    /// Sum of CPU, GPU, ANE and DRAM power.
     pub package_power: PowerSnapshot,
-    pub power_history: Vec<f32>,
+    pub dram_power_w: Option<f32>,
+    pub watts_per_ghz: Option<f32>,
+    /// powermetrics' `combined_power`, only when it diverges from the sum.
+    pub combined_power_w: Option<f32>,
+    /// One slot per sample interval; `None` where samples went missing.
+    pub power_history: Vec<Option<f32>>,
+    /// Fixed sparkline ceiling in watts (`--power-scale`); auto-scaled when
+    /// `None`.
+    pub power_scale: Option<f32>,
+    /// `ThermalLevel::severity` per sample, aligned with `power_history`.
+    pub thermal_history: Vec<Option<u8>>,
+    /// Time span covered by a full `power_history` buffer.
+    pub history_seconds: u64,
+    /// While paused, the sample under the history cursor: its clock time
+    /// and CPU+GPU watts.
+    pub history_cursor: Option<(String, f32)>,
+    /// Clock histories shown in the processor panel, toggled with `f`.
+    pub freq_history: Option<FreqHistory>,
+    pub watched: Option<WatchedProcess<'a>>,
+    /// Approximate whole-machine power in watts and its source.
+    pub system_power: Option<(f32, &'static str)>,
+    /// Package energy integrated since launch, in watt-hours.
+    pub energy_wh: f64,
+    /// System-wide energy impact, when the `tasks` sampler runs.
+    pub energy_impact: Option<f32>,
+    /// Top processes by energy impact, when that panel is visible.
+    pub top_processes: Option<&'a [TaskMetrics]>,
+}
+
+/// Internal state surfaced by `--verbose` / the 'd' key for bug reports.
+pub struct Diagnostics {
+    pub reader: ReaderStats,
+    pub dropped_samples: u64,
+    pub timecode: String,
+    /// GPU usage from powermetrics and from IOKit, when the two differ.
+    pub gpu_sources: Option<(u64, u64)>,
+    /// How the ANE usage figure was obtained.
+    pub ane_source: &'static str,
+    /// Error from the newest sample that failed to parse, while failing.
+    pub parse_failure: Option<String>,
+}
+
+/// Process selected with `--watch-process` and its latest task sample.
+pub struct WatchedProcess<'a> {
+    pub target: &'a str,
+    pub task: Option<&'a TaskMetrics>,
+    /// Whether the process has appeared in any sample so far.
+    pub seen: bool,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize)]
 pub struct PowerSnapshot {
     pub current: f32,
     pub average: f32,
     pub peak: f32,
+    /// 95th percentile over the averaging window.
+    pub p95: f32,
     pub percent_of_tdp: f32,
+    /// Current draw relative to the session peak.
+    pub percent_of_peak: f32,
+}
+
+impl PowerSnapshot {
+    /// The percentage and its label for `--percent-basis`.
+    fn percent(&self, basis: PercentBasis, strings: &Strings) -> (f32, &'static str) {
+        match basis {
+            PercentBasis::Tdp => (self.percent_of_tdp, "TDP"),
+            PercentBasis::Peak => (self.percent_of_peak, strings.peak),
+        }
+    }
+
+    /// `percent_of_tdp` is left at zero when `max_power` is unknown.
+    pub fn new(current: f32, average: f32, peak: f32, p95: f32, max_power: f32) -> Self {
+        let percent_of_tdp = if max_power > 0.0 {
+            (current / max_power * 100.0).clamp(0.0, 999.0)
+        } else {
+            0.0
+        };
+        let percent_of_peak = if peak > 0.0 {
+            (current / peak * 100.0).clamp(0.0, 100.0)
+        } else {
+            0.0
+        };
+        Self {
+            current,
+            average,
+            peak,
+            p95,
+            percent_of_tdp,
+            percent_of_peak,
+        }
+    }
+}
+
+/// One vertical slot of the main layout.
+enum Section<'s> {
+    Panel(Panel),
+    Watched(&'s WatchedProcess<'s>),
+    Top(&'s [TaskMetrics]),
 }
 
 pub fn draw(frame: &mut Frame<'_>, data: &UiSnapshot<'_>) {
+    let area = frame.area();
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        let message = format!(
+            "{} ({}x{}, need {MIN_WIDTH}x{MIN_HEIGHT})",
+            data.strings.terminal_too_small, area.width, area.height
+        );
+        let paragraph = Paragraph::new(message)
+            .style(Style::default().fg(data.theme.warning))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    // The process panels sit just above power, or at the bottom without it.
+    let mut process_sections = Vec::new();
+    if let Some(watched) = &data.watched {
+        process_sections.push(Section::Watched(watched));
+    }
+    if let Some(tasks) = data.top_processes {
+        process_sections.push(Section::Top(tasks));
+    }
+    let has_power = data.panels.contains(&Panel::Power);
+    let mut sections = Vec::new();
+    for panel in data.panels {
+        if *panel == Panel::Power {
+            sections.append(&mut process_sections);
+        }
+        sections.push(Section::Panel(*panel));
+    }
+    sections.append(&mut process_sections);
+    // A focused panel gets the whole screen.
+    if let Some(panel) = data.focus {
+        sections = vec![Section::Panel(panel)];
+    }
+
+    let constraints: Vec<Constraint> = sections
+        .iter()
+        .map(|section| match section {
+            _ if data.focus.is_some() => Constraint::Min(0),
+            // Without the power panel the processor panel takes the slack.
+            Section::Panel(Panel::Processor) if has_power => Constraint::Percentage(40),
+            Section::Panel(Panel::Processor) => Constraint::Min(10),
+            Section::Panel(Panel::Memory) => Constraint::Length(3),
+            Section::Panel(Panel::Io) => Constraint::Length(6),
+            Section::Panel(Panel::Power) => Constraint::Min(10),
+            Section::Watched(_) => Constraint::Length(3),
+            Section::Top(_) => Constraint::Length(8),
+        })
+        .collect();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(40),
-            Constraint::Length(3),
-            Constraint::Length(5),
-            Constraint::Min(10),
-        ])
+        .constraints(constraints)
         .split(frame.area());
 
-    draw_processor(frame, chunks[0], data);
-    draw_memory(frame, chunks[1], data);
-    draw_io(frame, chunks[2], data);
-    draw_power(frame, chunks[3], data);
+    for (section, area) in sections.iter().zip(chunks.iter()) {
+        match section {
+            // Memory and I/O are sampled locally and can be shown right away.
+            Section::Panel(Panel::Processor) if !data.initialized => {
+                draw_waiting(frame, *area, &data.soc.name, data)
+            }
+            Section::Panel(Panel::Power) if !data.initialized => {
+                draw_waiting(frame, *area, data.strings.power, data)
+            }
+            Section::Panel(Panel::Processor) => draw_processor(frame, *area, data),
+            Section::Panel(Panel::Memory) => draw_memory(frame, *area, data),
+            Section::Panel(Panel::Io) => draw_io(frame, *area, data),
+            Section::Panel(Panel::Power) => draw_power(frame, *area, data),
+            Section::Watched(watched) => {
+                draw_watched_process(frame, *area, watched, &data.theme, data.strings)
+            }
+            Section::Top(tasks) => {
+                draw_top_processes(frame, *area, tasks, &data.theme, data.strings)
+            }
+        }
+    }
+
+    if let Some(diagnostics) = &data.diagnostics {
+        draw_diagnostics(frame, diagnostics, &data.theme, data.strings);
+    }
+}
+
+/// Placeholder for panels fed by powermetrics until its first sample, so the
+/// startup window doesn't look like an idle machine.
+fn draw_waiting(frame: &mut Frame<'_>, area: Rect, title: &str, data: &UiSnapshot<'_>) {
+    let block = Block::default()
+        .title(title.to_string())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(data.theme.accent));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let line = centered_rect(inner, inner.width, 1);
+    let message = Paragraph::new(format!(
+        "{} ({}s)",
+        data.strings.waiting_for_powermetrics,
+        data.uptime.as_secs()
+    ))
+    .style(Style::default().fg(data.theme.muted))
+    .alignment(Alignment::Center);
+    frame.render_widget(message, line);
+}
+
+fn draw_diagnostics(
+    frame: &mut Frame<'_>,
+    diagnostics: &Diagnostics,
+    theme: &Theme,
+    strings: &'static Strings,
+) {
+    let mut lines = vec![
+        Line::from(format!(
+            "{}: {} {}",
+            strings.powermetrics_file, diagnostics.reader.file_len, strings.bytes
+        )),
+        Line::from(format!(
+            "{}: {:.2} ms",
+            strings.last_parse,
+            diagnostics.reader.parse_time.as_secs_f64() * 1000.0
+        )),
+        Line::from(format!(
+            "{}: {}",
+            strings.plist_chunks, diagnostics.reader.chunks
+        )),
+        Line::from(format!(
+            "{}: {}",
+            strings.dropped_samples, diagnostics.dropped_samples
+        )),
+        Line::from(format!("{}: {}", strings.timecode, diagnostics.timecode)),
+    ];
+    if let Some((powermetrics, iokit)) = diagnostics.gpu_sources {
+        lines.push(Line::from(format!(
+            "{}: powermetrics {powermetrics}% / IOKit {iokit}%",
+            strings.gpu_usage
+        )));
+    }
+    lines.push(Line::from(format!(
+        "{}: {}",
+        strings.ane_usage, diagnostics.ane_source
+    )));
+    if let Some(error) = &diagnostics.parse_failure {
+        lines.push(Line::styled(
+            format!("{}: {error}", strings.parse_error),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    let area = centered_rect(frame.area(), 44, lines.len() as u16 + 2);
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text))
+        .block(
+            Block::default()
+                .title(strings.diagnostics)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent)),
+        );
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// A `width` x `height` rectangle centered in `area`, clipped to fit.
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
 
 fn draw_processor(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
     let title = format!(
-        "{} (cores: {}E+{}P+{}GPU)",
-        data.soc.name, data.soc.e_core_count, data.soc.p_core_count, data.soc.gpu_core_count
+        "{} (cores: {}E+{}P+{}GPU) CPU {}%",
+        data.soc.name,
+        data.soc.e_core_count,
+        data.soc.p_core_count,
+        data.soc.gpu_core_count,
+        overall_cpu_pct(data.cpu, data.soc)
     );
-    let block = Block::default()
+    let strings = data.strings;
+    let mut clock = format!(
+        "{} {} | {} {}",
+        strings.uptime,
+        format_duration(data.uptime),
+        strings.sample,
+        data.sample_clock.as_deref().unwrap_or("--:--:--")
+    );
+    clock.push_str(&format!(
+        " | {} {}ms",
+        strings.every,
+        data.sample_interval.as_millis()
+    ));
+    if data.auto_interval {
+        clock.push_str(&format!(" ({})", strings.auto));
+    }
+    if interval_differs(data.sample_interval, data.requested_interval) {
+        clock.push_str(&format!(
+            " ({} {}ms)",
+            strings.asked,
+            data.requested_interval.as_millis()
+        ));
+    }
+    if data.dropped_samples > 0 {
+        clock.push_str(&format!(" | {} {}", strings.dropped, data.dropped_samples));
+    }
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .border_style(Style::default().fg(data.color));
+        .title(Line::from(clock).right_aligned())
+        .border_style(Style::default().fg(data.theme.accent));
+    if let Some(split) = cluster_power_split(data.cpu) {
+        block = block.title_bottom(split);
+    }
+    if let Some(battery) = &data.battery {
+        block = block.title_bottom(Line::from(battery_line(battery, data.strings)).right_aligned());
+    }
+    if let Some(gb) = data.gpu_memory_gb {
+        block = block.title_bottom(
+            Line::from(format!("{}: {gb:.1} GB", data.strings.gpu_memory)).centered(),
+        );
+    }
+    if data.thermal_throttle {
+        block = block.title(
+            Line::from(Span::styled(
+                format!(" {} ", data.strings.throttling),
+                Style::default()
+                    .fg(data.theme.critical)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ))
+            .centered(),
+        );
+    }
     frame.render_widget(block, area);
 
     let inner = area.inner(Margin {
         horizontal: 1,
         vertical: 1,
     });
+    if inner.width < PROCESSOR_COMPACT_WIDTH {
+        render_processor_compact(frame, inner, data);
+        return;
+    }
     let mut constraints = vec![Constraint::Length(2), Constraint::Length(2)];
+    if data.freq_history.is_some() {
+        constraints.push(Constraint::Length(FREQ_HISTORY_HEIGHT));
+    }
     if data.show_cores {
         constraints.push(Constraint::Min(0));
     }
@@ -97,26 +637,42 @@ fn draw_processor(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
         .split(sections[0]);
 
     let e_title = format!(
-        "E-CPU Usage: {}% @ {} MHz",
-        data.cpu.e_cluster_active, data.cpu.e_cluster_freq_mhz
+        "{}: {}% @ {}",
+        data.strings.e_cpu_usage,
+        data.cpu.e_cluster_active,
+        format_freq(
+            data.cpu.e_cluster_freq_mhz,
+            data.cpu.e_cluster_max_freq_mhz,
+            data.freq_unit
+        )
     );
     let p_title = format!(
-        "P-CPU Usage: {}% @ {} MHz",
-        data.cpu.p_cluster_active, data.cpu.p_cluster_freq_mhz
+        "{}: {}% @ {}",
+        data.strings.p_cpu_usage,
+        data.cpu.p_cluster_active,
+        format_freq(
+            data.cpu.p_cluster_freq_mhz,
+            data.cpu.p_cluster_max_freq_mhz,
+            data.freq_unit
+        )
     );
     render_usage_block(
         frame,
         cpu_chunks[0],
         e_title,
         data.cpu.e_cluster_active,
-        data.color,
+        &data.theme,
+        gauge_fill_color(data, data.cpu.e_cluster_active),
+        data.bar_chars,
     );
     render_usage_block(
         frame,
         cpu_chunks[2],
         p_title,
         data.cpu.p_cluster_active,
-        data.color,
+        &data.theme,
+        gauge_fill_color(data, data.cpu.p_cluster_active),
+        data.bar_chars,
     );
 
     let gpu_chunks = Layout::default()
@@ -128,51 +684,281 @@ fn draw_processor(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
         ])
         .split(sections[1]);
 
-    let gpu_title = format!(
-        "GPU Usage: {}% @ {} MHz",
-        data.gpu.active_pct, data.gpu.freq_mhz
-    );
+    let gpu_title = if data.gpu.available {
+        format!(
+            "{}: {}% @ {}",
+            data.strings.gpu_usage,
+            data.gpu.active_pct,
+            format_freq(data.gpu.freq_mhz, 0, data.freq_unit)
+        )
+    } else {
+        format!("{}: {}", data.strings.gpu_usage, data.strings.not_available)
+    };
     render_usage_block(
         frame,
         gpu_chunks[0],
         gpu_title,
         data.gpu.active_pct,
-        data.color,
+        &data.theme,
+        gauge_fill_color(data, data.gpu.active_pct),
+        data.bar_chars,
     );
 
     let ane_title = format!(
-        "ANE Usage: {}% @ {:.1} W",
-        data.ane_percent, data.ane_power_w
+        "{}: {}% @ {:.1} W",
+        data.strings.ane_usage, data.ane_percent, data.ane_power_w
     );
     render_usage_block(
         frame,
         gpu_chunks[2],
         ane_title,
         data.ane_percent,
-        data.color,
+        &data.theme,
+        gauge_fill_color(data, data.ane_percent),
+        data.bar_chars,
     );
 
+    let mut next_section = 2;
+    if let Some(history) = &data.freq_history {
+        render_freq_history(frame, sections[next_section], history, data);
+        next_section += 1;
+    }
+    if data.show_cores {
+        render_core_sections(frame, sections[next_section], data);
+    }
+}
+
+/// Title row plus two sparkline rows per frequency series.
+const FREQ_HISTORY_HEIGHT: u16 = 3;
+
+/// E, P and GPU clock sparklines side by side, each scaled from 0 to the
+/// series' boost ceiling (or its highest sample when that is unknown), so
+/// boost-then-settle and throttle dips read the same across panels.
+fn render_freq_history(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    history: &FreqHistory,
+    data: &UiSnapshot<'_>,
+) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+        ])
+        .split(area);
+    let series = [
+        ("E", &history.e_cluster, data.cpu.e_cluster_max_freq_mhz),
+        ("P", &history.p_cluster, data.cpu.p_cluster_max_freq_mhz),
+        ("GPU", &history.gpu, 0),
+    ];
+    for ((label, values, ceiling), column) in series.into_iter().zip(columns.iter()) {
+        let width = column.width.saturating_sub(1) as usize;
+        let start = values.len().saturating_sub(width);
+        // Right-align like the power history so the newest sample is last.
+        let mut values = values[start..].to_vec();
+        values.splice(0..0, std::iter::repeat_n(Some(0), width - values.len()));
+        let ceiling = values
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(ceiling);
+        let latest = values.last().copied().flatten().unwrap_or(0);
+        let title = format!(
+            "{label} {} {}",
+            freq_value(latest, data.freq_unit),
+            freq_unit_suffix(data.freq_unit)
+        );
+        let spark = Sparkline::default()
+            .block(Block::default().title(title))
+            .style(Style::default().fg(data.theme.frequency))
+            .absent_value_symbol(if data.ascii { "." } else { "░" })
+            .absent_value_style(Style::default().fg(data.theme.muted))
+            .max(ceiling.max(1))
+            .data(&values);
+        let spark = if data.ascii {
+            spark.bar_set(ASCII_BARS)
+        } else {
+            spark
+        };
+        let column = Rect {
+            width: column.width.saturating_sub(1),
+            ..*column
+        };
+        frame.render_widget(spark, column);
+    }
+}
+
+/// "3204/3504 MHz" when the boost ceiling is known, otherwise "3204 MHz";
+/// "3.20/3.50 GHz" with `--freq-unit ghz`.
+fn format_freq(freq_mhz: u64, max_freq_mhz: u64, unit: FreqUnit) -> String {
+    let suffix = freq_unit_suffix(unit);
+    if max_freq_mhz > 0 {
+        format!(
+            "{}/{} {suffix}",
+            freq_value(freq_mhz, unit),
+            freq_value(max_freq_mhz, unit)
+        )
+    } else {
+        format!("{} {suffix}", freq_value(freq_mhz, unit))
+    }
+}
+
+/// A frequency without its unit; GHz values keep two decimals so they stay
+/// four characters wide like typical MHz values.
+fn freq_value(freq_mhz: u64, unit: FreqUnit) -> String {
+    match unit {
+        FreqUnit::Mhz => freq_mhz.to_string(),
+        FreqUnit::Ghz => format!("{:.2}", freq_mhz as f64 / 1000.0),
+    }
+}
+
+fn freq_unit_suffix(unit: FreqUnit) -> &'static str {
+    match unit {
+        FreqUnit::Mhz => "MHz",
+        FreqUnit::Ghz => "GHz",
+    }
+}
+
+/// Pad `text` with spaces to `width` terminal columns. `format!` widths
+/// count chars, which misaligns double-width CJK labels.
+fn pad_to_width(text: &str, width: usize, right_align: bool) -> String {
+    let fill = " ".repeat(width.saturating_sub(Span::raw(text).width()));
+    if right_align {
+        fill + text
+    } else {
+        format!("{text}{fill}")
+    }
+}
+
+/// Compact battery status, e.g. "Battery 82% (3h05m) 12.4W".
+fn battery_line(battery: &BatteryStats, strings: &Strings) -> String {
+    let state = if battery.charging {
+        format!(" {}", strings.charging)
+    } else if battery.external_power {
+        format!(" {}", strings.on_ac)
+    } else if let Some(minutes) = battery.time_to_empty_min {
+        format!(" ({}h{:02}m)", minutes / 60, minutes % 60)
+    } else {
+        String::new()
+    };
+    format!(
+        "{} {}%{} {:.1}W",
+        strings.battery, battery.charge_percent, state, battery.system_power_w
+    )
+}
+
+/// Whole-CPU utilization like top's: the cluster loads weighted by their
+/// logical core counts, or a plain mean when the counts are unknown.
+fn overall_cpu_pct(cpu: &CpuMetrics, soc: &SocInfo) -> u64 {
+    let (e, p) = (soc.e_core_count as u64, soc.p_core_count as u64);
+    if e + p == 0 {
+        return (cpu.e_cluster_active + cpu.p_cluster_active) / 2;
+    }
+    (cpu.e_cluster_active * e + cpu.p_cluster_active * p + (e + p) / 2) / (e + p)
+}
+
+/// "E: x.xW / P: y.yW" when powermetrics reports per-cluster power.
+fn cluster_power_split(cpu: &CpuMetrics) -> Option<String> {
+    match (cpu.e_cluster_power_w, cpu.p_cluster_power_w) {
+        (None, None) => None,
+        (e, p) => {
+            let watts = |w: Option<f32>| w.map_or_else(|| "-".to_string(), |w| format!("{w:.1}W"));
+            Some(format!("E: {} / P: {}", watts(e), watts(p)))
+        }
+    }
+}
+
+/// Percentage-only processor view for very narrow terminals.
+fn render_processor_compact(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
+    let mut lines = vec![
+        Line::from(format!(
+            "E:{}% P:{}%",
+            data.cpu.e_cluster_active, data.cpu.p_cluster_active
+        )),
+        Line::from(if data.gpu.available {
+            format!("GPU:{}% ANE:{}%", data.gpu.active_pct, data.ane_percent)
+        } else {
+            format!(
+                "GPU:{} ANE:{}%",
+                data.strings.not_available, data.ane_percent
+            )
+        }),
+    ];
     if data.show_cores {
-        render_core_sections(frame, sections[2], data);
+        let per_line = (area.width as usize / CORE_COMPACT_ENTRY_WIDTH).max(1);
+        for (prefix, cores) in [("E", &data.cpu.e_cores), ("P", &data.cpu.p_cores)] {
+            lines.extend(compact_core_lines(prefix, cores, per_line, data));
+        }
     }
+    let paragraph = Paragraph::new(lines).style(Style::default().fg(data.theme.accent));
+    frame.render_widget(paragraph, area);
+}
+
+fn compact_core_lines(
+    prefix: &str,
+    cores: &[CoreMetrics],
+    per_line: usize,
+    data: &UiSnapshot<'_>,
+) -> Vec<Line<'static>> {
+    let theme = &data.theme;
+    let off = data.strings.core_off;
+    // Right-align the label in the 4 columns a `100%` reading takes.
+    let off_pad = " ".repeat(4usize.saturating_sub(Span::raw(off).width()));
+    cores
+        .chunks(per_line.max(1))
+        .map(|chunk| {
+            let spans: Vec<Span<'static>> = chunk
+                .iter()
+                .map(|core| {
+                    if core.down {
+                        return Span::styled(
+                            format!("{prefix}{:02} {off_pad}{off} ", core.id + 1),
+                            Style::default().fg(theme.muted),
+                        );
+                    }
+                    Span::styled(
+                        format!(
+                            "{prefix}{:02} {:>3}% ",
+                            core.id + 1,
+                            core.active_pct.min(999)
+                        ),
+                        Style::default().fg(theme.load_color(core.active_pct)),
+                    )
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
 }
 
 fn draw_memory(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
     let ram_title = if data.ram_has_swap {
         format!(
-            "RAM Usage: {:.1}/{:.1} GB - swap {:.1}/{:.1} GB",
-            data.memory.used_gb, data.memory.total_gb, data.swap_used_gb, data.swap_total_gb
+            "{}: {:.1}/{:.1} GB - {} {:.1}/{:.1} GB",
+            data.strings.ram_usage,
+            data.memory.used_gb,
+            data.memory.total_gb,
+            data.strings.swap,
+            data.swap_used_gb,
+            data.swap_total_gb
         )
     } else {
         format!(
-            "RAM Usage: {:.1}/{:.1} GB - swap inactive",
-            data.memory.used_gb, data.memory.total_gb
+            "{}: {:.1}/{:.1} GB - {}",
+            data.strings.ram_usage,
+            data.memory.used_gb,
+            data.memory.total_gb,
+            data.strings.swap_inactive
         )
     };
     let block = Block::default()
-        .title("Memory")
+        .title(data.strings.memory)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(data.color));
+        .border_style(Style::default().fg(data.theme.accent));
     frame.render_widget(block, area);
     let inner = area.inner(Margin {
         horizontal: 1,
@@ -180,58 +966,247 @@ fn draw_memory(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
     });
     let gauge = Gauge::default()
         .block(Block::default().title(ram_title))
-        .gauge_style(Style::default().fg(data.color))
+        .gauge_style(Style::default().fg(data.theme.accent))
         .percent(data.memory.used_percent as u16);
     frame.render_widget(gauge, inner);
 }
 
 fn draw_io(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
     let block = Block::default()
-        .title("I/O")
+        .title(data.strings.io)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(data.color));
+        .border_style(Style::default().fg(data.theme.accent));
     frame.render_widget(block, area);
     let inner = area.inner(Margin {
         horizontal: 1,
         vertical: 1,
     });
+    let mut sources = Vec::new();
+    if data.net_io {
+        sources.push((
+            data.strings.network_io,
+            vec![
+                (
+                    data.strings.net_in,
+                    format_net_rate_with_total(
+                        data.io.net_in_mbps,
+                        data.io.net_in_total,
+                        data.net_unit,
+                    ),
+                ),
+                (
+                    data.strings.net_out,
+                    format_net_rate_with_total(
+                        data.io.net_out_mbps,
+                        data.io.net_out_total,
+                        data.net_unit,
+                    ),
+                ),
+                (
+                    data.strings.packets,
+                    format!(
+                        "{:.0}/{:.0} pps in/out",
+                        data.io.net_in_pps, data.io.net_out_pps
+                    ),
+                ),
+            ],
+        ));
+    }
+    if data.disk_io {
+        sources.push((
+            data.strings.disk_io,
+            vec![
+                (
+                    data.strings.disk_read,
+                    format_rate_with_total(data.io.disk_read_mbps, data.io.disk_read_total),
+                ),
+                (
+                    data.strings.disk_write,
+                    format_rate_with_total(data.io.disk_write_mbps, data.io.disk_write_total),
+                ),
+            ],
+        ));
+    }
     let columns = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .constraints(vec![
+            Constraint::Ratio(1, sources.len().max(1) as u32);
+            sources.len()
+        ])
         .split(inner);
-    render_io_panel(
-        frame,
-        columns[0],
-        "Network I/O",
-        "In",
-        format_rate(data.io.net_in_mbps),
-        "Out",
-        format_rate(data.io.net_out_mbps),
-        data.color,
+    for ((title, rows), column) in sources.iter().zip(columns.iter()) {
+        render_io_panel(frame, *column, title, rows, &data.theme);
+    }
+}
+
+fn draw_watched_process(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    watched: &WatchedProcess<'_>,
+    theme: &Theme,
+    strings: &'static Strings,
+) {
+    let (title, line) = match watched.task {
+        Some(task) => (
+            format!("{}: {} (pid {})", strings.process, task.name, task.pid),
+            Line::from(format!(
+                "CPU {:.1}% | GPU {:.1}% | {} {:.1}",
+                task.cpu_percent(),
+                task.gpu_percent(),
+                strings.energy_impact,
+                task.energy_impact
+            )),
+        ),
+        None => (
+            format!("{}: {}", strings.process, watched.target),
+            if watched.seen {
+                Line::from(Span::styled(
+                    strings.process_exited,
+                    Style::default().fg(theme.warning),
+                ))
+            } else {
+                Line::from(Span::styled(
+                    strings.waiting_for_process,
+                    Style::default().fg(theme.muted),
+                ))
+            },
+        ),
+    };
+    let paragraph = Paragraph::new(line).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent)),
     );
-    render_io_panel(
-        frame,
-        columns[1],
-        "Disk I/O",
-        "Read",
-        format_rate(data.io.disk_read_mbps),
-        "Write",
-        format_rate(data.io.disk_write_mbps),
-        data.color,
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_top_processes(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    tasks: &[TaskMetrics],
+    theme: &Theme,
+    strings: &'static Strings,
+) {
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{} {:>7} {} {}",
+            pad_to_width(strings.column_name, 24, false),
+            "PID",
+            pad_to_width(strings.column_cpu_time, 9, true),
+            pad_to_width(strings.column_energy, 8, true),
+        ),
+        Style::default()
+            .fg(theme.label)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    if tasks.is_empty() {
+        lines.push(Line::from(Span::styled(
+            strings.waiting_for_tasks,
+            Style::default().fg(theme.muted),
+        )));
+    }
+    for task in tasks {
+        let name: String = task.name.chars().take(24).collect();
+        lines.push(Line::from(format!(
+            "{:<24} {:>7} {:>9.1} {:>8.1}",
+            name, task.pid, task.cpu_ms_per_s, task.energy_impact
+        )));
+    }
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title(strings.top_processes)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent)),
     );
+    frame.render_widget(paragraph, area);
 }
 
 fn draw_power(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
-    let block = Block::default()
-        .title(format!(
-            "CPU+GPU+ANE Power: {:.2}W (avg {:.2}W peak {:.2}W) throttle: {}",
-            data.package_power.current,
-            data.package_power.average,
-            data.package_power.peak,
-            if data.thermal_throttle { "yes" } else { "no" }
-        ))
+    let throttle = match data.thermal_level {
+        Some(level @ ThermalLevel::Normal) => Span::raw(level.to_string()),
+        Some(level @ ThermalLevel::Crisis) => {
+            Span::styled(level.to_string(), Style::default().fg(data.theme.critical))
+        }
+        Some(level @ ThermalLevel::Danger) => {
+            Span::styled(level.to_string(), Style::default().fg(data.theme.warning))
+        }
+        // Unmapped codes are not throttling, so show the raw code without alarm.
+        Some(level @ ThermalLevel::Unknown(_)) => {
+            Span::styled(level.to_string(), Style::default().fg(data.theme.muted))
+        }
+        None if data.thermal_throttle => {
+            Span::styled(data.strings.yes, Style::default().fg(data.theme.critical))
+        }
+        None => Span::raw(data.strings.no),
+    };
+    let components = if data.dram_power_w.is_some() {
+        "CPU+GPU+ANE+DRAM"
+    } else {
+        "CPU+GPU+ANE"
+    };
+    let strings = data.strings;
+    let mut spans = vec![Span::raw(format!(
+        "{components} {}: {:.2}W ({} {:.2}W {} {:.2}W {} {:.2}W) ",
+        strings.power,
+        data.package_power.current,
+        strings.avg,
+        data.package_power.average,
+        strings.p95,
+        data.package_power.p95,
+        strings.peak,
+        data.package_power.peak,
+    ))];
+    spans.push(Span::raw(match data.watts_per_ghz {
+        Some(efficiency) => format!("{efficiency:.1} W/GHz "),
+        None if data.ascii => "- W/GHz ".to_string(),
+        None => "— W/GHz ".to_string(),
+    }));
+    if let Some(combined) = data.combined_power_w {
+        spans.push(Span::styled(
+            format!("[powermetrics {combined:.2}W] "),
+            Style::default().fg(data.theme.warning),
+        ));
+    }
+    spans.push(Span::raw(format!("{}: ", data.strings.thermal)));
+    spans.push(throttle);
+    let title = Line::from(spans);
+    let border = if data.power_alert {
+        data.theme.critical
+    } else {
+        data.theme.accent
+    };
+    let mut block = Block::default()
+        .title(title)
+        .title(
+            Line::from(format!(
+                " {}: {:.2} Wh ",
+                data.strings.energy, data.energy_wh
+            ))
+            .right_aligned(),
+        )
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(data.color));
+        .border_style(Style::default().fg(border));
+    if let Some(status) = data.status {
+        block = block.title_bottom(Line::from(Span::styled(
+            format!(" {status} "),
+            Style::default().fg(data.theme.warning),
+        )));
+    }
+    if let Some((watts, source)) = data.system_power {
+        block = block.title_bottom(
+            Line::from(format!(
+                "{}: ~{watts:.1}W ({source})",
+                data.strings.system_power
+            ))
+            .centered(),
+        );
+    }
+    if let Some(impact) = data.energy_impact {
+        block = block.title_bottom(
+            Line::from(format!("{}: {impact:.0}", data.strings.energy_impact)).right_aligned(),
+        );
+    }
     frame.render_widget(block, area);
     let inner = area.inner(Margin {
         horizontal: 1,
@@ -239,25 +1214,40 @@ fn draw_power(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
     });
     let segments = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ])
         .split(inner);
 
     render_power_summary(frame, segments[0], data);
-    render_power_history(frame, segments[1], data);
+    render_power_budget(frame, segments[1], data);
+    let history = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(segments[2]);
+    render_power_history(frame, history[0], data);
+    render_thermal_strip(frame, history[1], data);
 }
 fn render_power_summary(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
+    let strings = data.strings;
+    let (cpu_percent, basis) = data.cpu_power.percent(data.percent_basis, strings);
     let cpu_line = format!(
-        "CPU: {:.2}W ({:.0}% TDP) avg {:.2}W peak {:.2}W",
+        "CPU: {:.2}W ({cpu_percent:.0}% {basis}) {} {:.2}W {} {:.2}W",
         data.cpu_power.current,
-        data.cpu_power.percent_of_tdp,
+        strings.avg,
         data.cpu_power.average,
+        strings.peak,
         data.cpu_power.peak
     );
+    let (gpu_percent, basis) = data.gpu_power.percent(data.percent_basis, strings);
     let gpu_line = format!(
-        "GPU: {:.2}W ({:.0}% TDP) avg {:.2}W peak {:.2}W",
+        "GPU: {:.2}W ({gpu_percent:.0}% {basis}) {} {:.2}W {} {:.2}W",
         data.gpu_power.current,
-        data.gpu_power.percent_of_tdp,
+        strings.avg,
         data.gpu_power.average,
+        strings.peak,
         data.gpu_power.peak
     );
     let columns = Layout::default()
@@ -265,55 +1255,169 @@ fn render_power_summary(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>
         .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
         .split(area);
     let cpu_paragraph = Paragraph::new(Line::from(cpu_line))
+        .style(Style::default().fg(data.theme.text))
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: true });
     let gpu_paragraph = Paragraph::new(Line::from(gpu_line))
+        .style(Style::default().fg(data.theme.text))
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: true });
     frame.render_widget(cpu_paragraph, columns[0]);
     frame.render_widget(gpu_paragraph, columns[1]);
 }
 
+/// Package power against the chip's estimated envelope (CPU + GPU caps),
+/// colored along the load gradient. Draw above the envelope pins the bar
+/// at full in the critical color.
+fn render_power_budget(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
+    let budget = data.soc.cpu_max_power + data.soc.gpu_max_power;
+    if budget <= 0.0 {
+        return;
+    }
+    let watts = data.package_power.current;
+    let percent = (watts / budget * 100.0).max(0.0).round() as u64;
+    let label = format!(
+        "{}: {watts:.1}/{budget:.0}W ({percent}%) ",
+        data.strings.power_budget
+    );
+    let (color, modifier) = if percent > 100 {
+        (data.theme.critical, Modifier::BOLD)
+    } else {
+        (data.theme.load_color(percent), Modifier::empty())
+    };
+    let label = Span::styled(label, Style::default().fg(data.theme.text));
+    let bar_width = area.width.saturating_sub(label.width() as u16);
+    let line = Line::from(vec![
+        label,
+        Span::styled(
+            block_bar(percent, bar_width, data.bar_chars),
+            Style::default().fg(color).add_modifier(modifier),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
 fn render_power_history(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
-    let peak_limit = data.package_power.peak.max(0.1);
+    let peak_limit = data
+        .power_scale
+        .unwrap_or_else(|| data.package_power.peak.max(0.1));
     let mut values = combined_history_values(&data.power_history, peak_limit);
+    if data.power_scale.is_some() {
+        // Readings above a pinned scale are clipped rather than rescaling.
+        values
+            .iter_mut()
+            .flatten()
+            .for_each(|value| *value = (*value).min(100));
+    }
     if area.width > 0 {
         let max_points = area.width as usize;
         if values.len() > max_points {
             let start = values.len() - max_points;
             values = values[start..].to_vec();
         } else if values.len() < max_points {
-            let mut padded = vec![0; max_points - values.len()];
+            let mut padded = vec![Some(0); max_points - values.len()];
             padded.extend(values);
             values = padded;
         }
     }
     if values.is_empty() {
-        values.push(0);
+        values.push(Some(0));
+    }
+    let max_value = values
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(100)
+        .max(100);
+    let mut title = vec![Span::raw(match data.power_scale {
+        Some(watts) => format!(
+            "{} ({} {}s, 0-{watts:.0}W)",
+            data.strings.power_history, data.strings.last, data.history_seconds
+        ),
+        None => format!(
+            "{} ({} {}s)",
+            data.strings.power_history, data.strings.last, data.history_seconds
+        ),
+    })];
+    if let Some((clock, watts)) = &data.history_cursor {
+        title.push(Span::styled(
+            format!(
+                " [{}] {clock} {watts:.2}W ({})",
+                data.strings.paused,
+                if data.ascii { "</>" } else { "←/→" }
+            ),
+            Style::default().fg(data.theme.warning),
+        ));
     }
-    let max_value = values.iter().copied().max().unwrap_or(100).max(100);
     let spark = Sparkline::default()
-        .style(Style::default().fg(data.color))
+        .block(Block::default().title(Line::from(title)))
+        .style(Style::default().fg(data.theme.accent))
+        .absent_value_symbol(if data.ascii { "." } else { "░" })
+        .absent_value_style(Style::default().fg(data.theme.muted))
         .direction(RenderDirection::LeftToRight)
         .max(max_value)
         .data(&values);
+    let spark = if data.ascii {
+        spark.bar_set(ASCII_BARS)
+    } else {
+        spark
+    };
     frame.render_widget(spark, area);
 }
 
-fn combined_history_values(history: &[f32], peak_limit: f32) -> Vec<u64> {
+/// One colored cell per sample under the power sparkline, right-aligned the
+/// same way so each cell sits below the power reading it was taken with.
+fn render_thermal_strip(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
+    let width = area.width as usize;
+    let start = data.thermal_history.len().saturating_sub(width);
+    let samples = &data.thermal_history[start..];
+    let mut spans = vec![Span::raw(" ".repeat(width - samples.len()))];
+    spans.extend(samples.iter().map(|severity| {
+        let (color, shade, ascii) = match severity {
+            None => return Span::raw(" "),
+            Some(0) => (data.theme.gradient[0], "░", "-"),
+            Some(1 | 2) => (data.theme.warning, "▒", "="),
+            Some(_) => (data.theme.critical, "█", "#"),
+        };
+        let glyph = if data.ascii {
+            ascii
+        } else if data.monochrome {
+            shade
+        } else {
+            "▀"
+        };
+        Span::styled(glyph, Style::default().fg(color))
+    }));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn combined_history_values(history: &[Option<f32>], peak_limit: f32) -> Vec<Option<u64>> {
     history
         .iter()
-        .map(|value| {
-            if peak_limit <= 0.0 {
-                (*value * 10.0).max(0.0).round() as u64
-            } else {
-                ((*value / peak_limit) * 100.0).round() as u64
-            }
+        .map(|slot| {
+            slot.map(|value| {
+                if peak_limit <= 0.0 {
+                    (value * 10.0).max(0.0).round() as u64
+                } else {
+                    ((value / peak_limit) * 100.0).round() as u64
+                }
+            })
         })
         .collect()
 }
 
 fn render_core_sections(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>) {
+    let area = if data.legend {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(area);
+        render_load_legend(frame, rows[1], &data.theme, data.bar_chars);
+        rows[0]
+    } else {
+        area
+    };
     let columns = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
@@ -321,18 +1425,18 @@ fn render_core_sections(frame: &mut Frame<'_>, area: Rect, data: &UiSnapshot<'_>
     render_core_panel(
         frame,
         columns[0],
-        "E-Cores",
+        data.strings.e_cores,
         "E",
         &data.cpu.e_cores,
-        data.color,
+        data,
     );
     render_core_panel(
         frame,
         columns[1],
-        "P-Cores",
+        data.strings.p_cores,
         "P",
         &data.cpu.p_cores,
-        data.color,
+        data,
     );
 }
 
@@ -342,22 +1446,23 @@ fn render_core_panel(
     title: &str,
     prefix: &str,
     cores: &[CoreMetrics],
-    accent: Color,
+    data: &UiSnapshot<'_>,
 ) {
-    let block = Block::default()
+    let theme = &data.theme;
+    let mut block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(accent));
-    frame.render_widget(block, area);
+        .border_style(Style::default().fg(theme.accent));
     let inner = area.inner(Margin {
         horizontal: 1,
         vertical: 1,
     });
     if inner.width == 0 || inner.height == 0 {
+        frame.render_widget(block, area);
         return;
     }
 
-    let columns = core_columns(inner.width, cores.len());
+    let columns = core_columns(inner.width, cores.len(), data.core_columns);
     let entry_width = if columns == 0 {
         inner.width as usize
     } else {
@@ -369,30 +1474,76 @@ fn render_core_panel(
     let mut lines: Vec<Line<'static>> = Vec::new();
     if cores.is_empty() {
         lines.push(Line::from(vec![Span::styled(
-            "未检测到核心",
-            Style::default().fg(Color::DarkGray),
+            data.strings.no_cores,
+            Style::default().fg(theme.muted),
         )]));
+    } else if (inner.width as usize) < CORE_MIN_ENTRY_WIDTH {
+        let per_line = (inner.width as usize / CORE_COMPACT_ENTRY_WIDTH).max(1);
+        lines.extend(compact_core_lines(prefix, cores, per_line, data));
     } else {
         for chunk in cores.chunks(columns.max(1)) {
             let mut spans: Vec<Span<'static>> = Vec::new();
             for core in chunk {
-                spans.extend(core_entry_spans(
-                    prefix,
-                    core,
-                    bar_width,
-                    accent,
-                    entry_width,
-                ));
+                spans.extend(core_entry_spans(prefix, core, bar_width, entry_width, data));
             }
             lines.push(Line::from(spans));
         }
     }
 
+    // Scroll only as far as the last full page of rows.
+    let visible = inner.height as usize;
+    let max_scroll = lines.len().saturating_sub(visible);
+    let scroll = data.core_scroll.min(max_scroll);
+    data.core_scroll_limit
+        .set(data.core_scroll_limit.get().max(Some(max_scroll)));
+    let more_above = scroll > 0;
+    let more_below = scroll < max_scroll;
+    if more_above || more_below {
+        let arrows = match (more_above, more_below, data.ascii) {
+            (true, true, false) => "▲/▼",
+            (true, false, false) => "▲",
+            (_, _, false) => "▼",
+            (true, true, true) => "^/v",
+            (true, false, true) => "^",
+            (_, _, true) => "v",
+        };
+        let hint = format!("{arrows} {}", data.strings.more);
+        block = block.title_bottom(
+            Line::from(Span::styled(hint, Style::default().fg(theme.muted))).right_aligned(),
+        );
+    }
+    frame.render_widget(block, area);
+
+    let lines: Vec<Line<'static>> = lines.into_iter().skip(scroll).take(visible).collect();
     let paragraph = Paragraph::new(lines);
     frame.render_widget(paragraph, inner);
 }
 
-fn core_columns(width: u16, count: usize) -> usize {
+/// One swatch per step of `Theme::load_color`.
+fn render_load_legend(frame: &mut Frame<'_>, area: Rect, theme: &Theme, bar_chars: BarChars) {
+    let thresholds = theme.load_thresholds;
+    let mut steps = vec![(0, format!("<{}", thresholds[0]))];
+    for pair in thresholds.windows(2) {
+        steps.push((pair[0], format!("<{}", pair[1])));
+    }
+    steps.push((thresholds[3], format!("{}+", thresholds[3])));
+    let mut spans = Vec::new();
+    for (percent, label) in steps {
+        spans.push(Span::styled(
+            bar_chars.filled.to_string().repeat(2),
+            Style::default().fg(theme.load_color(percent)),
+        ));
+        spans.push(Span::styled(
+            format!(" {label}%  "),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)).centered(), area);
+}
+
+/// Cores per row: at most `max`, and never so many that an entry drops
+/// below `CORE_MIN_ENTRY_WIDTH`.
+fn core_columns(width: u16, count: usize, max: usize) -> usize {
     if count == 0 {
         return 1;
     }
@@ -401,7 +1552,7 @@ fn core_columns(width: u16, count: usize) -> usize {
     if columns == 0 {
         columns = 1;
     }
-    columns = columns.min(CORE_MAX_COLUMNS);
+    columns = columns.min(max.max(1));
     columns.min(count)
 }
 
@@ -409,9 +1560,10 @@ fn core_entry_spans(
     prefix: &str,
     core: &CoreMetrics,
     bar_width: usize,
-    accent: Color,
     entry_width: usize,
+    data: &UiSnapshot<'_>,
 ) -> Vec<Span<'static>> {
+    let (theme, unit, bar_chars) = (&data.theme, data.freq_unit, data.bar_chars);
     let mut spans = Vec::new();
     let mut consumed = 0;
     let label = format!("{prefix}{:02}", core.id + 1);
@@ -419,24 +1571,48 @@ fn core_entry_spans(
     consumed += label_text.chars().count();
     spans.push(Span::styled(
         label_text,
-        Style::default().fg(accent).add_modifier(Modifier::BOLD),
+        Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
     ));
 
+    if core.down {
+        // Parked cores get a dotted bar and no frequency, so they are not
+        // mistaken for cores idling at their minimum clock.
+        let bar = if bar_chars.filled.is_ascii() {
+            "."
+        } else {
+            "·"
+        }
+        .repeat(bar_width);
+        let status = format!(" {}", data.strings.core_off);
+        consumed += bar.chars().count() + Span::raw(status.as_str()).width();
+        spans.push(Span::styled(bar, Style::default().fg(theme.muted)));
+        spans.push(Span::styled(
+            status,
+            Style::default().fg(theme.muted).add_modifier(Modifier::DIM),
+        ));
+        if consumed < entry_width {
+            spans.push(Span::raw(" ".repeat(entry_width - consumed)));
+        }
+        return spans;
+    }
+
     let clamped = core.active_pct.min(100) as usize;
     let filled = ((clamped * bar_width) + 99) / 100;
     let empty = bar_width.saturating_sub(filled);
     if filled > 0 {
-        let block = "█".repeat(filled);
+        let block = bar_chars.filled.to_string().repeat(filled);
         consumed += block.chars().count();
         spans.push(Span::styled(
             block,
-            Style::default().fg(core_usage_color(core.active_pct)),
+            Style::default().fg(theme.load_color(core.active_pct)),
         ));
     }
     if empty > 0 {
-        let pad = "░".repeat(empty);
+        let pad = bar_chars.empty.to_string().repeat(empty);
         consumed += pad.chars().count();
-        spans.push(Span::styled(pad, Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(pad, Style::default().fg(theme.muted)));
     }
 
     spans.push(Span::raw(" "));
@@ -446,17 +1622,21 @@ fn core_entry_spans(
     spans.push(Span::styled(
         percent_text,
         Style::default()
-            .fg(core_usage_color(core.active_pct))
+            .fg(theme.load_color(core.active_pct))
             .add_modifier(Modifier::BOLD),
     ));
     spans.push(Span::raw(" "));
     consumed += 1;
-    let freq_text = format!("{:>4}MHz", core.freq_mhz);
+    let freq_text = format!(
+        "{:>4}{}",
+        freq_value(core.freq_mhz, unit),
+        freq_unit_suffix(unit)
+    );
     consumed += freq_text.chars().count();
     spans.push(Span::styled(
         freq_text,
         Style::default()
-            .fg(Color::Yellow)
+            .fg(theme.frequency)
             .add_modifier(Modifier::BOLD),
     ));
 
@@ -467,39 +1647,44 @@ fn core_entry_spans(
     spans
 }
 
-fn core_usage_color(percent: u64) -> Color {
-    match percent {
-        90..=u64::MAX => Color::Red,
-        70..=89 => Color::LightRed,
-        50..=69 => Color::Yellow,
-        30..=49 => Color::LightGreen,
-        _ => Color::Cyan,
-    }
-}
-
 fn render_usage_block(
     frame: &mut Frame<'_>,
     area: Rect,
     title: String,
     percent: u64,
-    color: Color,
+    theme: &Theme,
+    fill: Color,
+    bar_chars: BarChars,
 ) {
     let bar_width = area.width.saturating_sub(2);
-    let bar = block_bar(percent, bar_width);
-    let lines = vec![Line::from(title), Line::from(bar)];
+    let bar = block_bar(percent, bar_width, bar_chars);
+    let lines = vec![
+        Line::from(title),
+        Line::from(Span::styled(bar, Style::default().fg(fill))),
+    ];
     let paragraph = Paragraph::new(lines)
-        .style(Style::default().fg(color))
+        .style(Style::default().fg(theme.accent))
         .wrap(Wrap { trim: true });
     frame.render_widget(paragraph, area);
 }
 
-fn block_bar(percent: u64, width: u16) -> String {
-    let width = width.max(10) as usize;
+/// Fill color for the cluster gauges: the load gradient, or the flat accent
+/// when `--no-load-color` is set.
+fn gauge_fill_color(data: &UiSnapshot<'_>, percent: u64) -> Color {
+    if data.load_color {
+        data.theme.load_color(percent)
+    } else {
+        data.theme.accent
+    }
+}
+
+fn block_bar(percent: u64, width: u16, bar_chars: BarChars) -> String {
+    let width = width as usize;
     let clamped = percent.min(100) as usize;
     let filled = (clamped * width + 99) / 100;
     let empty = width.saturating_sub(filled);
-    let filled_block = "█".repeat(filled);
-    let empty_block = "░".repeat(empty);
+    let filled_block = bar_chars.filled.to_string().repeat(filled);
+    let empty_block = bar_chars.empty.to_string().repeat(empty);
     format!("{filled_block}{empty_block}")
 }
 
@@ -507,28 +1692,18 @@ fn render_io_panel(
     frame: &mut Frame<'_>,
     area: Rect,
     title: &str,
-    first_label: &str,
-    first_value: String,
-    second_label: &str,
-    second_value: String,
-    color: Color,
+    rows: &[(&str, String)],
+    theme: &Theme,
 ) {
-    let lines = vec![
-        Line::from(vec![
-            Span::styled(
-                format!("{first_label:<5}"),
-                Style::default().fg(Color::Gray),
-            ),
-            Span::styled(first_value, Style::default().fg(color)),
-        ]),
-        Line::from(vec![
-            Span::styled(
-                format!("{second_label:<5}"),
-                Style::default().fg(Color::Gray),
-            ),
-            Span::styled(second_value, Style::default().fg(color)),
-        ]),
-    ];
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{label:<5}"), Style::default().fg(theme.label)),
+                Span::styled(value.clone(), Style::default().fg(theme.accent)),
+            ])
+        })
+        .collect();
     let paragraph = Paragraph::new(lines)
         .block(
             Block::default()
@@ -540,6 +1715,8 @@ fn render_io_panel(
     frame.render_widget(paragraph, area);
 }
 
+/// Humanize a MiB/s rate: GB/s from 1024, MB/s from 1, KB/s from 0.01 and
+/// whole B/s below that. Negative rates are shown as 0 B/s.
 fn format_rate(mbps: f32) -> String {
     let value = mbps.max(0.0);
     if value >= 1024.0 {
@@ -552,3 +1729,382 @@ fn format_rate(mbps: f32) -> String {
         format!("{:.0} B/s", (value * 1024.0 * 1024.0).round())
     }
 }
+
+/// Humanize a MiB/s rate as a link speed in decimal bits per second, like
+/// network gear quotes it: Gbps, Mbps, Kbps, then whole bps.
+fn format_bit_rate(mbps: f32) -> String {
+    let bits = f64::from(mbps.max(0.0)) * 1024.0 * 1024.0 * 8.0;
+    if bits >= 1e9 {
+        format!("{:.2} Gbps", bits / 1e9)
+    } else if bits >= 1e6 {
+        format!("{:.2} Mbps", bits / 1e6)
+    } else if bits >= 1e3 {
+        format!("{:.1} Kbps", bits / 1e3)
+    } else {
+        format!("{:.0} bps", bits.round())
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    let value = bytes as f64;
+    if value >= 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.2} GB", value / (1024.0 * 1024.0 * 1024.0))
+    } else if value >= 1024.0 * 1024.0 {
+        format!("{:.2} MB", value / (1024.0 * 1024.0))
+    } else if value >= 1024.0 {
+        format!("{:.1} KB", value / 1024.0)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+fn format_rate_with_total(mbps: f32, total_bytes: u64) -> String {
+    format!("{} (Σ {})", format_rate(mbps), format_bytes(total_bytes))
+}
+
+/// Like `format_rate_with_total`; the total stays in bytes either way.
+fn format_net_rate_with_total(mbps: f32, total_bytes: u64, unit: NetUnit) -> String {
+    match unit {
+        NetUnit::Bytes => format_rate_with_total(mbps, total_bytes),
+        NetUnit::Bits => format!(
+            "{} (Σ {})",
+            format_bit_rate(mbps),
+            format_bytes(total_bytes)
+        ),
+    }
+}
+
+/// Whether powermetrics clamped or rounded the requested interval by more
+/// than 10%.
+fn interval_differs(actual: Duration, requested: Duration) -> bool {
+    let requested = requested.as_secs_f32();
+    requested > 0.0 && (actual.as_secs_f32() - requested).abs() / requested > 0.1
+}
+
+/// `HH:MM:SS`, with hours allowed to exceed 24 for long sessions.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings;
+    use ratatui::backend::TestBackend;
+
+    const PANELS: [Panel; 4] = [Panel::Processor, Panel::Memory, Panel::Io, Panel::Power];
+
+    fn soc() -> SocInfo {
+        SocInfo {
+            name: "Apple M1".into(),
+            e_core_count: 4,
+            p_core_count: 4,
+            gpu_core_count: 8,
+            cpu_max_power: 20.0,
+            gpu_max_power: 20.0,
+            ane_max_power: 8.0,
+        }
+    }
+
+    fn snapshot<'a>(
+        soc: &'a SocInfo,
+        cpu: &'a CpuMetrics,
+        gpu: &'a GpuMetrics,
+        memory: &'a MemoryStats,
+    ) -> UiSnapshot<'a> {
+        let power = PowerSnapshot::new(1.5, 1.2, 3.0, 2.5, 20.0);
+        UiSnapshot {
+            soc,
+            cpu,
+            gpu,
+            memory,
+            io: IoStats::default(),
+            net_io: true,
+            disk_io: true,
+            thermal_throttle: false,
+            thermal_level: Some(ThermalLevel::Normal),
+            battery: None,
+            gpu_memory_gb: None,
+            power_alert: false,
+            status: None,
+            initialized: true,
+            theme: Theme::preset(ThemeName::Dark),
+            monochrome: false,
+            strings: &strings::EN,
+            uptime: Duration::from_secs(90),
+            sample_clock: None,
+            dropped_samples: 0,
+            sample_interval: Duration::from_secs(1),
+            requested_interval: Duration::from_secs(1),
+            auto_interval: false,
+            diagnostics: None,
+            show_cores: false,
+            panels: &PANELS,
+            focus: None,
+            core_scroll: 0,
+            core_scroll_limit: Cell::default(),
+            freq_unit: FreqUnit::Mhz,
+            net_unit: NetUnit::Bytes,
+            percent_basis: PercentBasis::Tdp,
+            bar_chars: BarChars::UNICODE,
+            ascii: false,
+            legend: false,
+            core_columns: 4,
+            load_color: false,
+            ane_percent: 0,
+            ane_power_w: 0.0,
+            ram_has_swap: false,
+            swap_used_gb: 0.0,
+            swap_total_gb: 0.0,
+            cpu_power: power,
+            gpu_power: power,
+            package_power: power,
+            dram_power_w: None,
+            watts_per_ghz: None,
+            combined_power_w: None,
+            power_history: vec![Some(1.0), Some(2.0), Some(1.5)],
+            power_scale: None,
+            thermal_history: Vec::new(),
+            history_seconds: 60,
+            history_cursor: None,
+            freq_history: None,
+            watched: None,
+            system_power: None,
+            energy_wh: 0.0,
+            energy_impact: None,
+            top_processes: None,
+        }
+    }
+
+    fn render(width: u16, height: u16, data: &UiSnapshot<'_>) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| draw(frame, data)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let mut text = String::new();
+        for y in 0..buffer.area.height {
+            // Wide glyphs fill the cell after them with a blank; skip it.
+            let mut x = 0;
+            while x < buffer.area.width {
+                let symbol = buffer[(x, y)].symbol();
+                text.push_str(symbol);
+                x += Span::raw(symbol).width().max(1) as u16;
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    #[test]
+    fn draws_gauges_and_power_title() {
+        let soc = soc();
+        let cpu = CpuMetrics {
+            e_cluster_active: 50,
+            ..CpuMetrics::default()
+        };
+        let (gpu, memory) = (GpuMetrics::default(), MemoryStats::default());
+        let text = render(100, 40, &snapshot(&soc, &cpu, &gpu, &memory));
+        assert!(text.contains("E-CPU Usage"), "{text}");
+        assert!(text.contains("CPU+GPU+ANE Power: 1.50W"), "{text}");
+    }
+
+    #[test]
+    fn throttling_badge_is_localized() {
+        let soc = soc();
+        let (cpu, gpu, memory) = (
+            CpuMetrics::default(),
+            GpuMetrics::default(),
+            MemoryStats::default(),
+        );
+        let mut data = snapshot(&soc, &cpu, &gpu, &memory);
+        data.thermal_throttle = true;
+        assert!(render(100, 40, &data).contains(" THROTTLING "));
+        data.strings = &strings::ZH;
+        let text = render(100, 40, &data);
+        assert!(text.contains("降频中"), "{text}");
+        assert!(!text.contains("THROTTLING"));
+    }
+
+    #[test]
+    fn diagnostics_overlay_is_localized() {
+        let soc = soc();
+        let (cpu, gpu, memory) = (
+            CpuMetrics::default(),
+            GpuMetrics::default(),
+            MemoryStats::default(),
+        );
+        let mut data = snapshot(&soc, &cpu, &gpu, &memory);
+        let diagnostics = || Diagnostics {
+            reader: ReaderStats::default(),
+            dropped_samples: 3,
+            timecode: "abc".into(),
+            gpu_sources: None,
+            ane_source: "",
+            parse_failure: None,
+        };
+        data.diagnostics = Some(diagnostics());
+        let text = render(100, 40, &data);
+        assert!(text.contains("Diagnostics"), "{text}");
+        assert!(text.contains("dropped samples: 3"), "{text}");
+        data.strings = &strings::ZH;
+        data.diagnostics = Some(diagnostics());
+        let text = render(100, 40, &data);
+        assert!(text.contains("诊断信息"), "{text}");
+        assert!(text.contains("丢弃样本: 3"), "{text}");
+    }
+
+    #[test]
+    fn clock_and_power_lines_are_localized() {
+        let soc = soc();
+        let (cpu, gpu, memory) = (
+            CpuMetrics::default(),
+            GpuMetrics::default(),
+            MemoryStats::default(),
+        );
+        let mut data = snapshot(&soc, &cpu, &gpu, &memory);
+        data.dropped_samples = 2;
+        let text = render(120, 40, &data);
+        assert!(
+            text.contains("up 00:01:30 | sample --:--:-- | every 1000ms | dropped 2"),
+            "{text}"
+        );
+        assert!(
+            text.contains("CPU: 1.50W (8% TDP) avg 1.20W peak 3.00W"),
+            "{text}"
+        );
+        data.strings = &strings::ZH;
+        let text = render(120, 40, &data);
+        assert!(
+            text.contains("运行 00:01:30 | 采样 --:--:-- | 间隔 1000ms | 丢弃 2"),
+            "{text}"
+        );
+        assert!(
+            text.contains("CPU: 1.50W (8% TDP) 均值 1.20W 峰值 3.00W"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn narrow_terminal_shows_message() {
+        let soc = soc();
+        let (cpu, gpu, memory) = (
+            CpuMetrics::default(),
+            GpuMetrics::default(),
+            MemoryStats::default(),
+        );
+        let text = render(40, 4, &snapshot(&soc, &cpu, &gpu, &memory));
+        assert!(
+            text.contains("Terminal too small (40x4, need 20x8)"),
+            "{text}"
+        );
+        assert!(!text.contains("E-CPU Usage"));
+    }
+
+    #[test]
+    fn too_small_guard_on_both_axes() {
+        let soc = soc();
+        let (cpu, gpu, memory) = (
+            CpuMetrics::default(),
+            GpuMetrics::default(),
+            MemoryStats::default(),
+        );
+        let data = snapshot(&soc, &cpu, &gpu, &memory);
+        for (width, height, too_small) in [
+            (1, 1, true),
+            (MIN_WIDTH - 1, MIN_HEIGHT, true),
+            (MIN_WIDTH, MIN_HEIGHT - 1, true),
+            (MIN_WIDTH, MIN_HEIGHT, false),
+        ] {
+            let text = render(width, height, &data);
+            // A single cell has no room for the message; it only must not panic.
+            if width > 1 {
+                assert_eq!(
+                    text.contains("Terminal too small"),
+                    too_small,
+                    "{width}x{height}:\n{text}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn narrow_widths_do_not_panic() {
+        let soc = soc();
+        let (cpu, gpu, memory) = (
+            CpuMetrics::default(),
+            GpuMetrics::default(),
+            MemoryStats::default(),
+        );
+        // Past the too-small guard at 20 columns, up to the compact layout.
+        for width in 1..PROCESSOR_COMPACT_WIDTH {
+            render(width, 40, &snapshot(&soc, &cpu, &gpu, &memory));
+        }
+    }
+
+    #[test]
+    fn compact_processor_shows_percentages_only() {
+        let soc = soc();
+        let cpu = CpuMetrics {
+            e_cluster_active: 42,
+            p_cluster_active: 88,
+            ..CpuMetrics::default()
+        };
+        let (gpu, memory) = (GpuMetrics::default(), MemoryStats::default());
+        let text = render(30, 40, &snapshot(&soc, &cpu, &gpu, &memory));
+        assert!(text.contains("E:42% P:88%"), "{text}");
+        assert!(!text.contains("E-CPU Usage"), "{text}");
+    }
+
+    #[test]
+    fn battery_line_is_localized() {
+        let battery = BatteryStats {
+            charge_percent: 82,
+            charging: true,
+            external_power: true,
+            time_to_empty_min: None,
+            system_power_w: 12.4,
+        };
+        assert_eq!(
+            battery_line(&battery, &strings::EN),
+            "Battery 82% charging 12.4W"
+        );
+        assert_eq!(
+            battery_line(&battery, &strings::ZH),
+            "电池 82% 充电中 12.4W"
+        );
+    }
+
+    #[test]
+    fn top_process_header_aligns_cjk_labels() {
+        let header = |strings: &'static Strings| {
+            let mut terminal = Terminal::new(TestBackend::new(60, 4)).unwrap();
+            terminal
+                .draw(|frame| {
+                    let theme = Theme::preset(ThemeName::Dark);
+                    draw_top_processes(frame, frame.area(), &[], &theme, strings);
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            // Column where "PID" starts on the header row.
+            (0..buffer.area.width)
+                .find(|&x| buffer[(x, 1)].symbol() == "P" && buffer[(x + 1, 1)].symbol() == "I")
+                .unwrap()
+        };
+        assert_eq!(header(&strings::EN), header(&strings::ZH));
+    }
+
+    #[test]
+    fn format_rate_unit_boundaries() {
+        assert_eq!(format_rate(0.009), "9437 B/s");
+        assert_eq!(format_rate(0.01), "10.2 KB/s");
+        assert_eq!(format_rate(0.99), "1013.8 KB/s");
+        assert_eq!(format_rate(1.0), "1.00 MB/s");
+        assert_eq!(format_rate(1023.99), "1023.99 MB/s");
+        assert_eq!(format_rate(1024.0), "1.00 GB/s");
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{
+    accelerator::AcceleratorStats, battery::BatteryStats, io_stats::IoStats, memory::MemoryStats,
+    powermetrics::PowermetricsReading, soc::SocInfo, thermal::ThermalLevel,
+};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    net::{IpAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Bumped whenever `WireSample` changes incompatibly.
+const WIRE_VERSION: u32 = 1;
+
+/// Longest request head accepted; anything bigger is not a dashboard poll.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// Keeps one stalled client from holding up the single serving thread.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Machine-side readings sent along with each sample, so a `--connect`
+/// client shows the remote machine's memory, I/O, battery, thermal level and
+/// GPU driver statistics, not its own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostStats {
+    pub soc: SocInfo,
+    pub memory: MemoryStats,
+    pub io: IoStats,
+    pub battery: Option<BatteryStats>,
+    /// Missing from older senders, which then show no thermal level.
+    #[serde(default)]
+    pub thermal_level: Option<ThermalLevel>,
+    #[serde(default)]
+    pub accelerator: Option<AcceleratorStats>,
+}
+
+/// What `/sample.json` serves and `--connect` consumes: the parsed
+/// powermetrics reading with the sender's `HostStats` attached.
+#[derive(Debug, Serialize, Deserialize)]
+struct WireSample {
+    version: u32,
+    reading: PowermetricsReading,
+}
+
+/// `--web`: a dashboard page at `/` that polls the JSON report at
+/// `/metrics.json`, plus the raw samples at `/sample.json` for `--connect`
+/// clients, all served from one port on a background thread.
+pub struct WebServer {
+    latest: Arc<Latest>,
+}
+
+/// Both documents are empty until the first reading has been published.
+#[derive(Default)]
+struct Latest {
+    report: Mutex<String>,
+    sample: Mutex<String>,
+}
+
+impl WebServer {
+    pub fn start(addr: IpAddr, port: u16) -> Result<Self> {
+        let listener = TcpListener::bind((addr, port))
+            .with_context(|| format!("failed to listen on {addr} port {port}"))?;
+        let latest = Arc::new(Latest::default());
+        let shared = Arc::clone(&latest);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // A client that hangs up mid-request is not worth reporting.
+                let _ = serve(stream, &shared);
+            }
+        });
+        Ok(Self { latest })
+    }
+
+    /// Replace the report served at `/metrics.json` and, when known, the
+    /// sample served at `/sample.json`.
+    pub fn publish(&self, report: &serde_json::Value, reading: Option<PowermetricsReading>) {
+        if let Ok(mut latest) = self.latest.report.lock() {
+            *latest = report.to_string();
+        }
+        let Some(reading) = reading else {
+            return;
+        };
+        let sample = WireSample {
+            version: WIRE_VERSION,
+            reading,
+        };
+        if let (Ok(json), Ok(mut latest)) =
+            (serde_json::to_string(&sample), self.latest.sample.lock())
+        {
+            *latest = json;
+        }
+    }
+}
+
+fn serve(mut stream: TcpStream, latest: &Latest) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+    let head = String::from_utf8_lossy(&request);
+    let mut words = head.split_whitespace();
+    let (method, path) = (words.next().unwrap_or(""), words.next().unwrap_or(""));
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/") => ("200 OK", "text/html; charset=utf-8", DASHBOARD.to_string()),
+        ("GET", "/metrics.json") => published(&latest.report),
+        ("GET", "/sample.json") => published(&latest.sample),
+        ("GET", _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_string(),
+        ),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body.as_bytes())?;
+    stream.flush()
+}
+
+/// A published JSON document, or 503 until the first one arrives.
+fn published(document: &Mutex<String>) -> (&'static str, &'static str, String) {
+    match document.lock().map(|json| json.clone()) {
+        Ok(json) if !json.is_empty() => ("200 OK", "application/json", json),
+        _ => (
+            "503 Service Unavailable",
+            "text/plain",
+            "no sample yet\n".to_string(),
+        ),
+    }
+}
+
+/// `--connect`: polls a remote `--web` server's `/sample.json` on a
+/// background thread and hands the results over a channel, so a slow or
+/// unreachable remote never stalls the UI. Every poll opens a fresh
+/// connection, so a dropped link or restarted server is picked up again on
+/// the next poll without any reconnect state.
+pub struct RemoteSource {
+    addr: String,
+    polls: Receiver<Result<WireSample, String>>,
+    last_timestamp: Option<SystemTime>,
+    /// Why the latest poll failed; cleared by the next good one.
+    error: Option<String>,
+}
+
+impl RemoteSource {
+    /// Start polling `addr` at half the interval, so samples are not shown a
+    /// full period late. The thread exits once the source is dropped.
+    pub fn new(addr: &str, interval: Duration) -> Self {
+        let (sender, polls) = mpsc::channel();
+        let target = addr.to_string();
+        thread::spawn(move || {
+            loop {
+                let next_due = Instant::now() + interval / 2;
+                let polled = fetch(&target).map_err(|err| format!("{err:#}"));
+                if sender.send(polled).is_err() {
+                    break;
+                }
+                thread::sleep(next_due.saturating_duration_since(Instant::now()));
+            }
+        });
+        Self {
+            addr: addr.to_string(),
+            polls,
+            last_timestamp: None,
+            error: None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The remote's newest sample, if one arrived since the last call.
+    /// Network failures are kept in `error` and retried; only an
+    /// incompatible wire format is returned as an error.
+    pub fn next(&mut self) -> Result<Option<PowermetricsReading>> {
+        let mut newest = None;
+        while let Ok(polled) = self.polls.try_recv() {
+            let sample = match polled {
+                Ok(sample) => sample,
+                Err(err) => {
+                    self.error = Some(err);
+                    continue;
+                }
+            };
+            self.error = None;
+            if sample.version != WIRE_VERSION {
+                bail!(
+                    "{} speaks wire format {}, expected {WIRE_VERSION}",
+                    self.addr,
+                    sample.version
+                );
+            }
+            let reading = sample.reading;
+            if self.last_timestamp != Some(reading.timestamp) {
+                self.last_timestamp = Some(reading.timestamp);
+                newest = Some(reading);
+            }
+        }
+        Ok(newest)
+    }
+}
+
+fn fetch(target: &str) -> Result<WireSample> {
+    let addr = target
+        .to_socket_addrs()?
+        .next()
+        .with_context(|| format!("{target} did not resolve"))?;
+    let mut stream = TcpStream::connect_timeout(&addr, CLIENT_TIMEOUT)?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    write!(
+        stream,
+        "GET /sample.json HTTP/1.1\r\nHost: {target}\r\nConnection: close\r\n\r\n"
+    )?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .context("malformed HTTP response")?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let status = head.lines().next().unwrap_or_default();
+    if !status.contains(" 200 ") {
+        bail!("{target}: {status}");
+    }
+    serde_json::from_slice(&response[split + 4..])
+        .with_context(|| format!("{target} sent an unreadable sample"))
+}
+
+const DASHBOARD: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>asitop</title>
+<style>
+  body { font: 14px ui-monospace, Menlo, monospace; background: #111; color: #ddd; margin: 2em; }
+  h1 { font-size: 16px; color: #6c6; }
+  .row { display: flex; align-items: center; margin: 6px 0; }
+  .label { width: 9em; }
+  .bar { flex: 1; max-width: 40em; height: 14px; background: #333; }
+  .fill { height: 100%; background: #6c6; width: 0; }
+  .value { width: 14em; padding-left: 1em; }
+  #status { color: #888; margin-top: 1em; }
+</style>
+</head>
+<body>
+<h1 id="soc">asitop</h1>
+<div id="gauges"></div>
+<div id="status">waiting for data…</div>
+<script>
+const gauges = [
+  ["E-CPU", r => r.cpu.e_cluster_active, r => r.cpu.e_cluster_active + "% @ " + r.cpu.e_cluster_freq_mhz + " MHz"],
+  ["P-CPU", r => r.cpu.p_cluster_active, r => r.cpu.p_cluster_active + "% @ " + r.cpu.p_cluster_freq_mhz + " MHz"],
+  ["GPU", r => r.gpu.active_pct, r => r.gpu.active_pct + "% @ " + r.gpu.freq_mhz + " MHz"],
+  ["RAM", r => r.memory.used_percent, r => r.memory.used_gb.toFixed(1) + "/" + r.memory.total_gb.toFixed(1) + " GB"],
+  ["CPU power", r => 100 * r.power.cpu.current / Math.max(r.power.cpu.peak, 0.1), r => r.power.cpu.current.toFixed(2) + " W"],
+  ["GPU power", r => 100 * r.power.gpu.current / Math.max(r.power.gpu.peak, 0.1), r => r.power.gpu.current.toFixed(2) + " W"],
+  ["Package", r => 100 * r.power.package.current / Math.max(r.power.package.peak, 0.1), r => r.power.package.current.toFixed(2) + " W"],
+];
+const root = document.getElementById("gauges");
+const rows = gauges.map(([label]) => {
+  const row = document.createElement("div");
+  row.className = "row";
+  row.innerHTML = '<span class="label"></span><div class="bar"><div class="fill"></div></div><span class="value"></span>';
+  row.querySelector(".label").textContent = label;
+  root.appendChild(row);
+  return row;
+});
+async function poll() {
+  try {
+    const response = await fetch("/metrics.json");
+    if (!response.ok) {
+      document.getElementById("status").textContent = "waiting for data…";
+      return;
+    }
+    const report = await response.json();
+    if (report.soc) {
+      document.getElementById("soc").textContent = report.soc.name;
+      gauges.forEach(([, percent, text], i) => {
+        rows[i].querySelector(".fill").style.width = Math.min(100, Math.max(0, percent(report))) + "%";
+        rows[i].querySelector(".value").textContent = text(report);
+      });
+      document.getElementById("status").textContent =
+        "sample " + (report.sample_time || "--:--:--") + " · thermal " + (report.thermal_level || report.thermal_pressure);
+    }
+  } catch (err) {
+    document.getElementById("status").textContent = "disconnected: " + err;
+  }
+}
+poll();
+setInterval(poll, 1000);
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(latest: &Latest, path: &str) -> String {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        write!(client, "GET {path} HTTP/1.1\r\n\r\n").unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        serve(stream, latest).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn metrics_are_unavailable_until_published() {
+        let latest = Latest::default();
+        assert!(get(&latest, "/metrics.json").starts_with("HTTP/1.1 503 "));
+
+        *latest.report.lock().unwrap() = "{}".to_string();
+        let response = get(&latest, "/metrics.json");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("Content-Type: application/json"));
+        assert!(response.ends_with("\r\n\r\n{}"));
+    }
+
+    #[test]
+    fn unresponsive_remote_does_not_block_polling() {
+        // Accepted by the backlog but never answered, so every fetch waits
+        // out `CLIENT_TIMEOUT` on the polling thread.
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let mut remote = RemoteSource::new(&addr, Duration::from_millis(100));
+        let started = Instant::now();
+        for _ in 0..5 {
+            assert!(remote.next().unwrap().is_none());
+            thread::sleep(Duration::from_millis(50));
+        }
+        assert!(started.elapsed() < CLIENT_TIMEOUT);
+        assert!(remote.error().is_none());
+    }
+
+    #[test]
+    fn web_listens_on_loopback_by_default() {
+        use clap::Parser;
+        let cli = crate::config::Cli::parse_from(["asitop", "--web", "8080"]);
+        assert!(cli.web_bind.is_loopback());
+        assert!(crate::config::Cli::try_parse_from(["asitop", "--web-bind", "0.0.0.0"]).is_err());
+    }
+}